@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Approve, Mint, SetAuthority, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::token::{Approve, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use degenrand::program::Degenrand;
 use std::mem::size_of;
 mod utils;
 pub use utils::*;
@@ -15,7 +17,23 @@ declare_id!("H2LCFgiKNFwdZyVQoJFhhhygvvuV8twbfzJ8nJpJHgG1");
  * oracle_vault: PDA owned by the degenrand Program for paying Oracle
  * degenrand_program: The Program Address for the degenrand Program
  * coin: PDA owned by degendevil used for storing data
+ *       Dice are coins with a roll-under target, sharing every account of a house coin
+ * counter: PDA owned by degendevil numbering the coins of an initiator
  * vault: PDA owned by degendevil used for escrowing sol and paying winner
+ *        SOL coins escrow their stake in the vault lamports, keyed by the native mint
+ * escrow: Token account owned by the vault escrowing the Token A stakes of a coin
+ *         Filled by delegated coins only once they are flipped or accepted
+ * receipt: PDA owned by degendevil recording the outcome of a single flip
+ * player_stats: PDA owned by degendevil tallying the flips of a player in a mint
+ * config: PDA owned by degendevil holding the admin and settings of the game
+ * prize_vault: PDA owned by degendevil holding the reward paid out in Token B
+ * prize_token: Token account owned by the prize_vault escrowing Token B
+ * tier_table: PDA owned by degendevil holding the odds of a mint by bet size
+ * mint_config: PDA owned by degendevil allowing a mint to be staked within its bet limits
+ * treasury: PDA owned by degendevil holding the house bankroll of a mint
+ * treasury_token: Token account owned by the treasury escrowing the bankroll
+ * jackpot: PDA owned by degendevil growing a pool from a slice of every stake in a mint
+ * jackpot_token: Token account owned by the jackpot escrowing the pool
  * initiator: The account creating the coin
  * acceptor: The account accepting the offer to flip
  * rent: The Rent Program
@@ -29,14 +47,46 @@ declare_id!("H2LCFgiKNFwdZyVQoJFhhhygvvuV8twbfzJ8nJpJHgG1");
 
 const COIN_PREFIX: &str = "DEGENDEVIL_COIN_SEED_V1.0";
 const VAULT_PREFIX: &str = "DEGENDEVIL_VAULT_SEED_V1.0";
-const WINNER_PREFIX: &str = "DEGENDEVIL_WINNER_SEED_V1.0";
+const ESCROW_PREFIX: &str = "DEGENDEVIL_ESCROW_SEED_V1.0";
+const RECEIPT_PREFIX: &str = "DEGENDEVIL_RECEIPT_SEED_V1.0";
+const COUNTER_PREFIX: &str = "DEGENDEVIL_COUNTER_SEED_V1.0";
+const CONFIG_PREFIX: &str = "DEGENDEVIL_CONFIG_SEED_V1.0";
+const PRIZE_VAULT_PREFIX: &str = "DEGENDEVIL_PRIZE_VAULT_SEED_V1.0";
+const PRIZE_TOKEN_PREFIX: &str = "DEGENDEVIL_PRIZE_TOKEN_SEED_V1.0";
+const TIERS_PREFIX: &str = "DEGENDEVIL_TIERS_SEED_V1.0";
+const MINT_CONFIG_PREFIX: &str = "DEGENDEVIL_MINT_CONFIG_SEED_V1.0";
+const STATS_PREFIX: &str = "DEGENDEVIL_STATS_SEED_V1.0";
+const JACKPOT_PREFIX: &str = "DEGENDEVIL_JACKPOT_SEED_V1.0";
+const JACKPOT_TOKEN_PREFIX: &str = "DEGENDEVIL_JACKPOT_TOKEN_SEED_V1.0";
+const TREASURY_PREFIX: &str = "DEGENDEVIL_TREASURY_SEED_V1.0";
+const TREASURY_TOKEN_PREFIX: &str = "DEGENDEVIL_TREASURY_TOKEN_SEED_V1.0";
 const ORACLE_FEE: u64 = 495000;
+const MAX_TIERS: usize = 8;
+const MAX_FEE_RECIPIENTS: usize = 8;
+const MAX_BPS: u16 = 10_000;
+const TIER_DECIMALS: u8 = 2;
+const MAX_PPM: u32 = 1_000_000;
+/// The flip reads the random bytes before the offset, the jackpot the ones after
+const JACKPOT_OFFSET: usize = 32;
+/// Dice roll in `0..DICE_SIDES` and win under a target in `DICE_MIN_TARGET..=DICE_MAX_TARGET`
+const DICE_SIDES: u8 = 100;
+const DICE_MIN_TARGET: u8 = 2;
+const DICE_MAX_TARGET: u8 = 98;
+/// Taken off the fair payout of a dice win, standing in for the fees of a coin
+const DICE_EDGE_BPS: u16 = 100;
+/// Most a keeper earns per coin, well within the rent of the coin and its randomness accounts
+const MAX_KEEPER_REWARD: u64 = 1_000_000;
+/// Sides of a coin, the acceptor of a P2P coin holding the one the initiator did not pick
+pub const HEADS: u8 = 0;
+pub const TAILS: u8 = 1;
+/// Seconds a receipt is kept after the reveal before its initiator may close it
+pub const RECEIPT_RETENTION: i64 = 90 * 24 * 60 * 60;
 
 #[program]
 pub mod degendevil {
     use std::ops::DerefMut;
 
-    use spl_token::instruction::AuthorityType;
+    use anchor_lang::solana_program::hash::hash;
 
     use super::*;
 
@@ -45,23 +95,455 @@ pub mod degendevil {
         _accounts: &[AccountInfo<'info>],
         _data: &[u8],
     ) -> Result<()> {
-        return Err(DegenErrorCode::FallBacked.into());
+        Err(DegenErrorCode::FallBacked.into())
     }
 
-    pub fn create_coin(
-        ctx: Context<CreateCoin>,
+    /**
+     * Only the upgrade authority of the program can take the admin seat
+     */
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        config_bump: u8,
+        refund_timeout: i64,
+        oracle: Pubkey,
+    ) -> Result<()> {
+        if refund_timeout <= 0 {
+            return Err(DegenErrorCode::InvalidRefundTimeout.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+
+        config.admin = ctx.accounts.admin.key();
+        config.refund_timeout = refund_timeout;
+        config.fee_bps = 0;
+        config.referral_bps = 0;
+        config.paused = false;
+        config.keeper_reward = 0;
+        config.oracle = oracle;
+        config.bump = config_bump;
+
+        Ok(())
+    }
+
+    pub fn set_refund_timeout(ctx: Context<UpdateConfig>, refund_timeout: i64) -> Result<()> {
+        if refund_timeout <= 0 {
+            return Err(DegenErrorCode::InvalidRefundTimeout.into());
+        }
+
+        ctx.accounts.config.refund_timeout = refund_timeout;
+
+        Ok(())
+    }
+
+    /**
+     * `fee_bps` of every settled stake is charged, `referral_bps` of that fee goes to the referrer
+     */
+    pub fn set_fees(ctx: Context<UpdateConfig>, fee_bps: u16, referral_bps: u16) -> Result<()> {
+        if fee_bps > MAX_BPS || referral_bps > MAX_BPS {
+            return Err(DegenErrorCode::InvalidFees.into());
+        }
+
+        let config = &mut ctx.accounts.config;
+
+        config.fee_bps = fee_bps;
+        config.referral_bps = referral_bps;
+
+        Ok(())
+    }
+
+    /**
+     * First step of rotating the admin, nothing changes until the new admin accepts
+     */
+    pub fn propose_admin(ctx: Context<UpdateConfig>, new_admin: Pubkey) -> Result<()> {
+        ctx.accounts.config.pending_admin = new_admin;
+
+        Ok(())
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+
+        config.admin = config.pending_admin;
+        config.pending_admin = Pubkey::default();
+
+        Ok(())
+    }
+
+    /**
+     * Circuit breaker, no new coin is created, flipped or accepted until unpaused while existing
+     * ones can still be revealed, cancelled or refunded
+     */
+    pub fn pause(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.config.paused = true;
+
+        Ok(())
+    }
+
+    pub fn unpause(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.config.paused = false;
+
+        Ok(())
+    }
+
+    /**
+     * Lamports a keeper earns for settling the coin of someone else, 0 to crank for free.
+     * Capped by `MAX_KEEPER_REWARD` so that the rent returned with a coin covers it.
+     */
+    pub fn set_keeper_reward(ctx: Context<UpdateConfig>, keeper_reward: u64) -> Result<()> {
+        if keeper_reward > MAX_KEEPER_REWARD {
+            return Err(DegenErrorCode::InvalidKeeperReward.into());
+        }
+
+        ctx.accounts.config.keeper_reward = keeper_reward;
+
+        Ok(())
+    }
+
+    /**
+     * Oracle answering the requests of new coins, those already created keep their own
+     */
+    pub fn set_oracle(ctx: Context<UpdateConfig>, oracle: Pubkey) -> Result<()> {
+        ctx.accounts.config.oracle = oracle;
+
+        Ok(())
+    }
+
+    /**
+     * Registers the token account collecting fees in its mint, replacing any previous one
+     */
+    pub fn set_fee_recipient(ctx: Context<SetFeeRecipient>) -> Result<()> {
+        let mint = ctx.accounts.fee_recipient.mint;
+        let token_account = ctx.accounts.fee_recipient.key();
+        let recipients = &mut ctx.accounts.config.fee_recipients;
+
+        if let Some(recipient) = recipients
+            .iter_mut()
+            .find(|recipient| recipient.mint == mint)
+        {
+            recipient.token_account = token_account;
+        } else if recipients.len() < MAX_FEE_RECIPIENTS {
+            recipients.push(FeeRecipient {
+                mint,
+                token_account,
+            });
+        } else {
+            return Err(DegenErrorCode::TooManyFeeRecipients.into());
+        }
+
+        Ok(())
+    }
+
+    pub fn init_prize_vault(
+        ctx: Context<InitPrizeVault>,
+        prize_vault_bump: u8,
+        reward: u64,
+    ) -> Result<()> {
+        let prize_vault = &mut ctx.accounts.prize_vault;
+
+        prize_vault.prize_mint = ctx.accounts.prize_mint.key();
+        prize_vault.prize_token = ctx.accounts.prize_token.key();
+        prize_vault.reward = reward;
+        prize_vault.bump = prize_vault_bump;
+
+        Ok(())
+    }
+
+    pub fn set_prize_reward(ctx: Context<SetPrizeReward>, reward: u64) -> Result<()> {
+        ctx.accounts.prize_vault.reward = reward;
+
+        Ok(())
+    }
+
+    pub fn init_tier_table(
+        ctx: Context<InitTierTable>,
+        tier_table_bump: u8,
+        tiers: Vec<Tier>,
+    ) -> Result<()> {
+        validate_tiers(&tiers)?;
+
+        let tier_table = &mut ctx.accounts.tier_table;
+
+        tier_table.mint = ctx.accounts.mint.key();
+        tier_table.tiers = tiers;
+        tier_table.bump = tier_table_bump;
+
+        Ok(())
+    }
+
+    pub fn set_tiers(ctx: Context<SetTiers>, tiers: Vec<Tier>) -> Result<()> {
+        validate_tiers(&tiers)?;
+
+        ctx.accounts.tier_table.tiers = tiers;
+
+        Ok(())
+    }
+
+    /**
+     * Allows `mint` to be staked for `prize_mint` with the odds of its tier table
+     */
+    pub fn init_mint_config(
+        ctx: Context<InitMintConfig>,
+        mint_config_bump: u8,
+        enabled: bool,
+        min_bet: u64,
+        max_bet: u64,
+        prize_mint: Pubkey,
+    ) -> Result<()> {
+        validate_bet_limits(min_bet, max_bet)?;
+
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        mint_config.mint = ctx.accounts.mint.key();
+        mint_config.enabled = enabled;
+        mint_config.min_bet = min_bet;
+        mint_config.max_bet = max_bet;
+        mint_config.tier_table = ctx.accounts.tier_table.key();
+        mint_config.prize_mint = prize_mint;
+        mint_config.bump = mint_config_bump;
+
+        Ok(())
+    }
+
+    pub fn set_mint_config(
+        ctx: Context<SetMintConfig>,
+        enabled: bool,
+        min_bet: u64,
+        max_bet: u64,
+        prize_mint: Pubkey,
+    ) -> Result<()> {
+        validate_bet_limits(min_bet, max_bet)?;
+
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        mint_config.enabled = enabled;
+        mint_config.min_bet = min_bet;
+        mint_config.max_bet = max_bet;
+        mint_config.prize_mint = prize_mint;
+
+        Ok(())
+    }
+
+    pub fn init_treasury(ctx: Context<InitTreasury>, treasury_bump: u8) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+
+        treasury.mint = ctx.accounts.mint.key();
+        treasury.treasury_token = ctx.accounts.treasury_token.key();
+        treasury.bump = treasury_bump;
+
+        Ok(())
+    }
+
+    pub fn deposit_bankroll(ctx: Context<DepositBankroll>, amount: u64) -> Result<()> {
+        anchor_spl::token::transfer(ctx.accounts.token_deposit_ctx(), amount)
+    }
+
+    pub fn withdraw_bankroll(ctx: Context<WithdrawBankroll>, amount: u64) -> Result<()> {
+        // The payouts reserved for pending coins stay in the bankroll
+        let bankroll = ctx.accounts.treasury_token.amount;
+
+        if ctx.accounts.treasury.available(bankroll) < amount {
+            return Err(DegenErrorCode::InsufficientFunds.into());
+        }
+
+        let treasury = &ctx.accounts.treasury;
+
+        let treasury_seeds = &[
+            TREASURY_PREFIX.as_bytes(),
+            treasury.mint.as_ref(),
+            &[treasury.bump],
+        ];
+
+        anchor_spl::token::transfer(
+            ctx.accounts
+                .token_withdraw_ctx()
+                .with_signer(&[treasury_seeds]),
+            amount,
+        )
+    }
+
+    /**
+     * The SOL bankroll is held in the lamports of the treasury of the native mint
+     */
+    pub fn init_sol_treasury(ctx: Context<InitSolTreasury>, treasury_bump: u8) -> Result<()> {
+        let treasury = &mut ctx.accounts.treasury;
+
+        treasury.mint = spl_token::native_mint::id();
+        treasury.treasury_token = Pubkey::default();
+        treasury.bump = treasury_bump;
+
+        Ok(())
+    }
+
+    pub fn deposit_sol_bankroll(ctx: Context<DepositSolBankroll>, amount: u64) -> Result<()> {
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.admin.key(),
+            &ctx.accounts.treasury.key(),
+            amount,
+        );
+
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.admin.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn withdraw_sol_bankroll(ctx: Context<WithdrawSolBankroll>, amount: u64) -> Result<()> {
+        let treasury = ctx.accounts.treasury.to_account_info();
+
+        // The treasury stays rent exempt, with the payouts reserved for pending coins
+        if ctx.accounts.treasury.available(sol_bankroll(&treasury)?) < amount {
+            return Err(DegenErrorCode::InsufficientFunds.into());
+        }
+
+        **treasury.try_borrow_mut_lamports()?.deref_mut() -= amount;
+        **ctx
+            .accounts
+            .destination
+            .try_borrow_mut_lamports()?
+            .deref_mut() += amount;
+
+        Ok(())
+    }
+
+    pub fn init_jackpot(
+        ctx: Context<InitJackpot>,
+        jackpot_bump: u8,
+        contribution_bps: u16,
+        odds_ppm: u32,
+    ) -> Result<()> {
+        validate_jackpot(contribution_bps, odds_ppm)?;
+
+        let jackpot = &mut ctx.accounts.jackpot;
+
+        jackpot.mint = ctx.accounts.mint.key();
+        jackpot.jackpot_token = ctx.accounts.jackpot_token.key();
+        jackpot.contribution_bps = contribution_bps;
+        jackpot.odds_ppm = odds_ppm;
+        jackpot.bump = jackpot_bump;
+
+        Ok(())
+    }
+
+    pub fn set_jackpot(
+        ctx: Context<SetJackpot>,
+        contribution_bps: u16,
+        odds_ppm: u32,
+    ) -> Result<()> {
+        validate_jackpot(contribution_bps, odds_ppm)?;
+
+        let jackpot = &mut ctx.accounts.jackpot;
+
+        jackpot.contribution_bps = contribution_bps;
+        jackpot.odds_ppm = odds_ppm;
+
+        Ok(())
+    }
+
+    pub fn init_counter(ctx: Context<InitCounter>, counter_bump: u8) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+
+        counter.count = 0;
+        counter.bump = counter_bump;
+
+        Ok(())
+    }
+
+    /**
+     * Coin staking `amount` of Token A, escrowed by the vault until the coin settles.
+     * A `delegated` coin leaves the stake in the initiator ata with the vault approved to
+     * pull it, for players who keep their funds until the coin is played. The ata must not
+     * approve anyone else. The stake is escrowed when the coin is flipped or accepted, which
+     * fails once the approval is revoked, and a cancelled coin revokes it.
+     * `side` decides P2P coins only. House coins are won at the odds of their tier whatever the
+     * side, which then only sets the side recorded as landed.
+     * A `referrer` passes their token account of the mint as the first remaining account.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_coin<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, CreateCoin<'info>>,
         coin_bump: u8,
         vault_bump: u8,
         amount: u64,
+        decimals: u8,
+        p2p: bool,
+        side: u8,
+        delegated: bool,
+        referrer: Option<Pubkey>,
     ) -> Result<()> {
+        // The amount is in base units, the decimals guard against a client mistaking the mint
+        if decimals != ctx.accounts.mint.decimals {
+            return Err(DegenErrorCode::InvalidDecimals.into());
+        }
+
+        validate_side(side)?;
+
+        let scaled = calculate_amount(amount, decimals)?;
+
+        ctx.accounts.mint_config.check_bet(amount)?;
+
+        if ctx.accounts.initiator_ata.amount < amount {
+            return Err(DegenErrorCode::InsufficientFunds.into());
+        }
+
+        // An ata approves a single delegate, approving the vault would revoke another coin
+        if delegated && ctx.accounts.initiator_ata.delegate.is_some() {
+            return Err(DegenErrorCode::DelegateInUse.into());
+        }
+
+        // The referrer is paid in the mint of the coin and cannot be the initiator
+        if let Some(referrer) = referrer {
+            let referrer_ata: Account<TokenAccount> = Account::try_from(
+                ctx.remaining_accounts
+                    .get(0)
+                    .ok_or(DegenErrorCode::InvalidReferrer)?,
+            )
+            .map_err(|_| DegenErrorCode::InvalidReferrer)?;
+
+            if referrer_ata.key() != referrer
+                || referrer_ata.mint != ctx.accounts.mint.key()
+                || referrer_ata.owner == ctx.accounts.initiator.key()
+            {
+                return Err(DegenErrorCode::InvalidReferrer.into());
+            }
+        }
+
+        // House coins are locked at the odds of their tier, the payout of a win is set aside
+        // in the bankroll so that every pending coin can be paid in full
+        let (win_bps, payout) = if p2p {
+            (0, 0)
+        } else {
+            let win_bps = tier_probability(&ctx.accounts.tier_table.tiers, scaled);
+
+            (win_bps, calculate_payout(amount, win_bps))
+        };
+
+        let bankroll = ctx.accounts.treasury_token.amount;
+        ctx.accounts.treasury.reserve(bankroll, payout)?;
+
         let authority_key = ctx.accounts.initiator.key();
+        let nonce = ctx.accounts.counter.count;
+
+        ctx.accounts.counter.count += 1;
+
         // Set data for PDAs
         {
             let coin = &mut ctx.accounts.coin.load_init()?;
             let clock: Clock = Clock::get()?;
 
             coin.initiator = authority_key;
+            coin.nonce = nonce;
             coin.is_flipping = false;
+            coin.is_p2p = p2p;
+            coin.is_delegated = delegated;
+            coin.side = side;
+            coin.referrer = referrer.unwrap_or_default();
+            coin.initiator_ata = ctx.accounts.initiator_ata.key();
             coin.created_at = clock.unix_timestamp;
             coin.bump = coin_bump;
 
@@ -69,259 +551,3270 @@ pub mod degendevil {
 
             vault.coin_info = CoinInfo {
                 amount,
+                decimals,
                 mint_token: ctx.accounts.mint.key(),
+                prize_mint: ctx.accounts.prize_vault.prize_mint,
             };
 
+            vault.win_bps = win_bps;
+            vault.payout = payout;
+            vault.nonce = nonce;
             vault.bump = vault_bump;
+
+            // Opened on the first coin of the initiator in this mint
+            let player_stats = &mut ctx.accounts.player_stats;
+
+            if player_stats.player == Pubkey::default() {
+                player_stats.player = authority_key;
+                player_stats.mint = ctx.accounts.mint.key();
+                player_stats.bump = *ctx.bumps.get("player_stats").unwrap();
+            }
         }
 
-        degenrand::cpi::transfer_authority(ctx.accounts.coin_transfer_authority_ctx())?;
+        // Every coin owns its own requester so several coins can be flipping at once
+        let nonce_bytes = nonce.to_le_bytes();
 
-        // Delegate the Vault to be able to transfer SPL token from initiator and acceptor atas.
-        // Assume authority over ata of initiator
-        anchor_spl::token::set_authority(
-            ctx.accounts.token_set_authority_ctx(),
-            AuthorityType::AccountOwner,
-            Some(ctx.accounts.vault.key()),
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            authority_key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        let (_, request_bump) = degenrand::requestor_pda(&ctx.accounts.coin.key());
+        let (_, oracle_vault_bump) = degenrand::vault_pda(&ctx.accounts.coin.key());
+
+        degenrand::cpi::initialize(
+            ctx.accounts
+                .requester_initialize_ctx()
+                .with_signer(&[coin_seeds]),
+            request_bump,
+            oracle_vault_bump,
         )?;
 
-        let cpi_accounts = degenrand::cpi::accounts::RequestRandom {
-            requester: ctx.accounts.requester.to_account_info(),
-            vault: ctx.accounts.oracle_vault.clone(),
-            authority: ctx.accounts.coin.to_account_info(),
-            oracle: ctx.accounts.oracle.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-        };
+        if delegated {
+            // The stake stays with the initiator, the vault may only pull it on a loss
+            anchor_spl::token::approve(ctx.accounts.token_approve_ctx(), amount)?;
+        } else {
+            // Only the stake leaves the initiator ata, escrowed by the vault until the coin settles
+            anchor_spl::token::transfer(ctx.accounts.token_escrow_ctx(), amount)?;
+        }
+
+        emit!(CoinCreated {
+            coin: ctx.accounts.coin.key(),
+            initiator: authority_key,
+            mint: ctx.accounts.mint.key(),
+            amount,
+            nonce,
+            is_p2p: p2p,
+            is_sol: false,
+            is_delegated: delegated,
+            referrer: referrer.unwrap_or_default(),
+        });
+
+        Ok(())
+    }
+
+    /**
+     * House coin staking `amount` lamports, escrowed in the vault until the reveal.
+     * SOL coins are settled against the SOL treasury without fees, prize or jackpot.
+     */
+    pub fn create_sol_coin(
+        ctx: Context<CreateSolCoin>,
+        coin_bump: u8,
+        vault_bump: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let scaled = calculate_amount(amount, spl_token::native_mint::DECIMALS)?;
+
+        ctx.accounts.mint_config.check_bet(amount)?;
+
+        if ctx.accounts.initiator.lamports() < amount {
+            return Err(DegenErrorCode::InsufficientFunds.into());
+        }
+
+        // Same amount based tiers as the token coins, the payout is set aside in the SOL bankroll
+        let win_bps = tier_probability(&ctx.accounts.tier_table.tiers, scaled);
+        let payout = calculate_payout(amount, win_bps);
+        let bankroll = sol_bankroll(&ctx.accounts.treasury.to_account_info())?;
+
+        ctx.accounts.treasury.reserve(bankroll, payout)?;
+
+        let authority_key = ctx.accounts.initiator.key();
+        let nonce = ctx.accounts.counter.count;
+
+        ctx.accounts.counter.count += 1;
+
+        {
+            let coin = &mut ctx.accounts.coin.load_init()?;
+            let clock: Clock = Clock::get()?;
+
+            coin.initiator = authority_key;
+            coin.nonce = nonce;
+            coin.is_flipping = false;
+            coin.is_p2p = false;
+            coin.is_sol = true;
+            coin.initiator_ata = authority_key;
+            coin.created_at = clock.unix_timestamp;
+            coin.bump = coin_bump;
+
+            let vault = &mut ctx.accounts.vault;
+
+            vault.coin_info = CoinInfo {
+                amount,
+                decimals: spl_token::native_mint::DECIMALS,
+                mint_token: spl_token::native_mint::id(),
+                prize_mint: Pubkey::default(),
+            };
+
+            vault.win_bps = win_bps;
+            vault.payout = payout;
+            vault.nonce = nonce;
+            vault.bump = vault_bump;
 
-        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key);
+            let player_stats = &mut ctx.accounts.player_stats;
+
+            if player_stats.player == Pubkey::default() {
+                player_stats.player = authority_key;
+                player_stats.mint = spl_token::native_mint::id();
+                player_stats.bump = *ctx.bumps.get("player_stats").unwrap();
+            }
+        }
+
+        let nonce_bytes = nonce.to_le_bytes();
 
         let coin_seeds = &[
             COIN_PREFIX.as_bytes(),
-            ctx.accounts.initiator.key.as_ref(),
+            authority_key.as_ref(),
+            &nonce_bytes,
             &[coin_bump],
         ];
 
-        let signer = &[&coin_seeds[..]];
-
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.degenrand_program.clone(),
-            cpi_accounts,
-            signer,
-        );
+        let (_, request_bump) = degenrand::requestor_pda(&ctx.accounts.coin.key());
+        let (_, oracle_vault_bump) = degenrand::vault_pda(&ctx.accounts.coin.key());
 
-        degenrand::cpi::request_random(cpi_context)?;
+        degenrand::cpi::initialize(
+            ctx.accounts
+                .requester_initialize_ctx()
+                .with_signer(&[coin_seeds]),
+            request_bump,
+            oracle_vault_bump,
+        )?;
 
+        // Escrow the stake on top of the rent of the vault
         let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &ctx.accounts.initiator.key(),
-            &ctx.accounts.oracle_vault.key(),
-            ORACLE_FEE,
+            &authority_key,
+            &ctx.accounts.vault.key(),
+            amount,
         );
 
         anchor_lang::solana_program::program::invoke(
             &ix,
             &[
                 ctx.accounts.initiator.to_account_info(),
-                ctx.accounts.oracle_vault.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
             ],
         )?;
 
+        emit!(CoinCreated {
+            coin: ctx.accounts.coin.key(),
+            initiator: authority_key,
+            mint: spl_token::native_mint::id(),
+            amount,
+            nonce,
+            is_p2p: false,
+            is_sol: true,
+            is_delegated: false,
+            referrer: Pubkey::default(),
+        });
+
         Ok(())
     }
 
-    pub fn reveal_coin<'key, 'accounts, 'remaining, 'info>(
-        ctx: Context<'key, 'accounts, 'remaining, 'info, RevealCoin<'info>>,
+    /**
+     * House dice staking `amount` of Token A on a roll under `target`, the player picking the
+     * odds rather than the tiers. Flipped with flip_coin, cancelled and refunded as a coin.
+     */
+    pub fn create_dice(
+        ctx: Context<CreateDice>,
+        coin_bump: u8,
+        vault_bump: u8,
+        amount: u64,
+        decimals: u8,
+        target: u8,
     ) -> Result<()> {
-        {
-            if ctx.accounts.authority.key() != ctx.accounts.initiator.key() {
-                return Err(DegenErrorCode::Unauthorized.into());
-            }
+        if decimals != ctx.accounts.mint.decimals {
+            return Err(DegenErrorCode::InvalidDecimals.into());
         }
-        // Determine winner from random number
-        {
-            let requester_loader: AccountLoader<degenrand::Requester> =
-                AccountLoader::try_from_unchecked(ctx.program_id, &ctx.accounts.requester)?;
 
-            let requester = requester_loader.load()?;
+        validate_dice_target(target)?;
 
-            if requester.active_request {
-                return Err(DegenErrorCode::OracleNotCompleted.into());
+        calculate_amount(amount, decimals)?;
+
+        ctx.accounts.mint_config.check_bet(amount)?;
+
+        if ctx.accounts.initiator_ata.amount < amount {
+            return Err(DegenErrorCode::InsufficientFunds.into());
+        }
+
+        // The payout of the target is set aside in the bankroll until the reveal
+        let payout = calculate_dice_payout(amount, target);
+        let bankroll = ctx.accounts.treasury_token.amount;
+
+        ctx.accounts.treasury.reserve(bankroll, payout)?;
+
+        let authority_key = ctx.accounts.initiator.key();
+        let nonce = ctx.accounts.counter.count;
+
+        ctx.accounts.counter.count += 1;
+
+        {
+            let coin = &mut ctx.accounts.coin.load_init()?;
+            let clock: Clock = Clock::get()?;
+
+            coin.initiator = authority_key;
+            coin.nonce = nonce;
+            coin.is_flipping = false;
+            coin.is_p2p = false;
+            coin.dice_target = target;
+            coin.initiator_ata = ctx.accounts.initiator_ata.key();
+            coin.created_at = clock.unix_timestamp;
+            coin.bump = coin_bump;
+
+            let vault = &mut ctx.accounts.vault;
+
+            vault.coin_info = CoinInfo {
+                amount,
+                decimals,
+                mint_token: ctx.accounts.mint.key(),
+                prize_mint: Pubkey::default(),
+            };
+
+            vault.win_bps = dice_probability(target);
+            vault.payout = payout;
+            vault.nonce = nonce;
+            vault.bump = vault_bump;
+
+            let player_stats = &mut ctx.accounts.player_stats;
+
+            if player_stats.player == Pubkey::default() {
+                player_stats.player = authority_key;
+                player_stats.mint = ctx.accounts.mint.key();
+                player_stats.bump = *ctx.bumps.get("player_stats").unwrap();
+            }
+        }
+
+        let nonce_bytes = nonce.to_le_bytes();
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            authority_key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        let (_, request_bump) = degenrand::requestor_pda(&ctx.accounts.coin.key());
+        let (_, oracle_vault_bump) = degenrand::vault_pda(&ctx.accounts.coin.key());
+
+        degenrand::cpi::initialize(
+            ctx.accounts
+                .requester_initialize_ctx()
+                .with_signer(&[coin_seeds]),
+            request_bump,
+            oracle_vault_bump,
+        )?;
+
+        // Only the stake leaves the initiator ata, escrowed by the vault until the reveal
+        anchor_spl::token::transfer(ctx.accounts.token_escrow_ctx(), amount)?;
+
+        emit!(CoinCreated {
+            coin: ctx.accounts.coin.key(),
+            initiator: authority_key,
+            mint: ctx.accounts.mint.key(),
+            amount,
+            nonce,
+            is_p2p: false,
+            is_sol: false,
+            is_delegated: false,
+            referrer: Pubkey::default(),
+        });
+
+        Ok(())
+    }
+
+    pub fn flip_coin<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, FlipCoin<'info>>,
+    ) -> Result<()> {
+        let (is_delegated, initiator_ata_key) = {
+            let coin = &mut ctx.accounts.coin.load_mut()?;
+
+            // P2P coins are flipped by the acceptor in accept_coin
+            if coin.is_p2p {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            if coin.is_flipping {
+                return Err(DegenErrorCode::InflightRequest.into());
             }
 
-            let status =
-                calculate_probability(ctx.accounts.vault.coin_info.amount, &requester.random) != 0;
+            coin.is_flipping = true;
+
+            (coin.is_delegated, coin.initiator_ata)
+        };
+
+        let nonce_bytes = ctx.accounts.vault.nonce.to_le_bytes();
 
+        // A delegated stake is escrowed once the outcome is requested, revoking the approval
+        // afterwards can no longer dodge a loss
+        if is_delegated {
+            let initiator_ata = ctx
+                .remaining_accounts
+                .get(0)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+            let escrow = ctx
+                .remaining_accounts
+                .get(1)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+
+            if initiator_ata.key() != initiator_ata_key
+                || escrow.key() != escrow_pda(&ctx.accounts.vault.key()).0
             {
-                let winner_pda = &mut ctx.accounts.winner;
-                winner_pda.status = status;
-                winner_pda.winner = ctx.accounts.initiator.key();
+                return Err(DegenErrorCode::Unauthorized.into());
             }
 
             let (_, vault_bump) = vault_pda(
                 &ctx.accounts.vault.coin_info.mint_token,
                 ctx.accounts.initiator.key,
+                ctx.accounts.vault.nonce,
             );
 
-            let signer_seeds = &[
+            let vault_seeds = &[
                 VAULT_PREFIX.as_bytes(),
                 ctx.accounts.vault.coin_info.mint_token.as_ref(),
                 ctx.accounts.initiator.key.as_ref(),
+                &nonce_bytes,
                 ctx.program_id.as_ref(),
                 &[vault_bump],
             ];
 
+            pull_delegated_stake(
+                ctx.accounts.token_program.to_account_info(),
+                initiator_ata,
+                escrow.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.vault.coin_info.amount,
+                vault_seeds,
+            )?;
+
+            // No approval to the vault outlives the coin
+            let ix = spl_token::instruction::revoke(
+                &spl_token::id(),
+                initiator_ata.key,
+                ctx.accounts.initiator.key,
+                &[],
+            )?;
+
+            anchor_lang::solana_program::program::invoke(
+                &ix,
+                &[
+                    initiator_ata.to_account_info(),
+                    ctx.accounts.initiator.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+            )?;
+        }
+
+        let cpi_accounts = degenrand::cpi::accounts::RequestRandom {
+            requester: ctx.accounts.requester.to_account_info(),
+            vault: ctx.accounts.oracle_vault.clone(),
+            authority: ctx.accounts.coin.to_account_info(),
+            oracle: ctx.accounts.oracle.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+
+        let (_coin_authority, coin_bump) =
+            coin_pda(ctx.accounts.initiator.key, ctx.accounts.vault.nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        let signer = &[&coin_seeds[..]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.degenrand_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        degenrand::cpi::request_random(cpi_context)?;
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.initiator.key(),
+            &ctx.accounts.oracle_vault.key(),
+            ORACLE_FEE,
+        );
+
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.initiator.to_account_info(),
+                ctx.accounts.oracle_vault.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn cancel_coin(ctx: Context<CancelCoin>) -> Result<()> {
+        let (is_sol, is_delegated) = {
+            let coin = ctx.accounts.coin.load()?;
+
+            // Funds may only be withdrawn while no request is in flight
+            if coin.is_flipping {
+                return Err(DegenErrorCode::InflightRequest.into());
+            }
+
+            if coin.initiator_ata != ctx.accounts.initiator_ata.key() {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            (coin.is_sol, coin.is_delegated)
+        };
+
+        let reserved = ctx.accounts.vault.payout;
+        ctx.accounts.treasury.release(reserved);
+
+        let nonce = ctx.accounts.vault.nonce;
+        let nonce_bytes = nonce.to_le_bytes();
+
+        let (_, vault_bump) = vault_pda(
+            &ctx.accounts.vault.coin_info.mint_token,
+            ctx.accounts.initiator.key,
+            nonce,
+        );
+
+        let vault_seeds = &[
+            VAULT_PREFIX.as_bytes(),
+            ctx.accounts.vault.coin_info.mint_token.as_ref(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            ctx.program_id.as_ref(),
+            &[vault_bump],
+        ];
+
+        if is_delegated {
+            let ix = spl_token::instruction::revoke(
+                &spl_token::id(),
+                &ctx.accounts.initiator_ata.key(),
+                ctx.accounts.initiator.key,
+                &[],
+            )?;
+
+            anchor_lang::solana_program::program::invoke(
+                &ix,
+                &[
+                    ctx.accounts.initiator_ata.to_account_info(),
+                    ctx.accounts.initiator.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+            )?;
+        } else if !is_sol {
             anchor_spl::token::transfer(
                 ctx.accounts
-                    .token_transfer_ctx(
-                        ctx.accounts.vault.to_account_info(),
-                        ctx.accounts.initiator_ata.to_account_info(),
-                        ctx.accounts.admin_ata.to_account_info(),
-                    )
-                    .with_signer(&[signer_seeds]),
+                    .token_release_ctx(ctx.accounts.initiator_ata.to_account_info())
+                    .with_signer(&[vault_seeds]),
                 ctx.accounts.vault.coin_info.amount,
             )?;
+        } else {
+            // The lamports staked on a SOL coin go to the initiator, the vault rent to the recipient
+            let vault = ctx.accounts.vault.to_account_info();
+            let amount = ctx.accounts.vault.coin_info.amount;
+
+            **vault.try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.initiator.try_borrow_mut_lamports()? += amount;
+        }
 
-            anchor_spl::token::set_authority(
+        if !is_sol {
+            anchor_spl::token::close_account(
                 ctx.accounts
-                    .token_reset_initiator_authority_ctx()
-                    .with_signer(&[signer_seeds]),
-                AuthorityType::AccountOwner,
-                Some(ctx.accounts.initiator.key()),
+                    .token_close_escrow_ctx()
+                    .with_signer(&[vault_seeds]),
             )?;
         }
 
-        // Transfer back ownership of requester
-        let coin_acc = &ctx.remaining_accounts[0];
+        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key, nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        // The requester rent lands on the coin, which is closed to the recipient
+        degenrand::cpi::remove_pdas(
+            ctx.accounts
+                .coin_remove_requester_ctx()
+                .with_signer(&[coin_seeds]),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn accept_coin<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, AcceptCoin<'info>>,
+    ) -> Result<()> {
+        let amount = ctx.accounts.vault.coin_info.amount;
+
+        let (is_delegated, initiator_ata_key) = {
+            let coin = &mut ctx.accounts.coin.load_mut()?;
+
+            if !coin.is_p2p {
+                return Err(DegenErrorCode::NotP2p.into());
+            }
+
+            if coin.is_flipping {
+                return Err(DegenErrorCode::InflightRequest.into());
+            }
+
+            if ctx.accounts.acceptor.key() == coin.initiator {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            if ctx.accounts.acceptor_ata.amount < amount {
+                return Err(DegenErrorCode::InsufficientFunds.into());
+            }
+
+            // The oracle of the coin was fixed at creation, the acceptor only stakes against
+            // the one the admin trusts
+            let requester_loader: AccountLoader<degenrand::Requester> =
+                AccountLoader::try_from_unchecked(ctx.program_id, &ctx.accounts.requester)?;
+
+            if requester_loader.load()?.oracle != ctx.accounts.config.oracle {
+                return Err(DegenErrorCode::InvalidOracle.into());
+            }
+
+            coin.acceptor = ctx.accounts.acceptor.key();
+            coin.acceptor_ata = ctx.accounts.acceptor_ata.key();
+            coin.is_flipping = true;
+
+            (coin.is_delegated, coin.initiator_ata)
+        };
+
+        {
+            let acceptor_stats = &mut ctx.accounts.acceptor_stats;
+
+            if acceptor_stats.player == Pubkey::default() {
+                acceptor_stats.player = ctx.accounts.acceptor.key();
+                acceptor_stats.mint = ctx.accounts.vault.coin_info.mint_token;
+                acceptor_stats.bump = *ctx.bumps.get("acceptor_stats").unwrap();
+            }
+        }
+
+        anchor_spl::token::transfer(ctx.accounts.token_escrow_ctx(), amount)?;
+
+        // A delegated initiator stake is escrowed alongside once the coin is matched. The vault
+        // was approved for the stake alone, pulling it clears the approval.
+        if is_delegated {
+            let initiator_ata = ctx
+                .remaining_accounts
+                .get(0)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+
+            if initiator_ata.key() != initiator_ata_key {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            let nonce_bytes = ctx.accounts.vault.nonce.to_le_bytes();
+
+            let (_, vault_bump) = vault_pda(
+                &ctx.accounts.vault.coin_info.mint_token,
+                ctx.accounts.initiator.key,
+                ctx.accounts.vault.nonce,
+            );
+
+            let vault_seeds = &[
+                VAULT_PREFIX.as_bytes(),
+                ctx.accounts.vault.coin_info.mint_token.as_ref(),
+                ctx.accounts.initiator.key.as_ref(),
+                &nonce_bytes,
+                ctx.program_id.as_ref(),
+                &[vault_bump],
+            ];
+
+            pull_delegated_stake(
+                ctx.accounts.token_program.to_account_info(),
+                initiator_ata,
+                ctx.accounts.escrow.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                amount,
+                vault_seeds,
+            )?;
+        }
 
-        let cpi_accounts = degenrand::cpi::accounts::TransferAuthority {
+        let cpi_accounts = degenrand::cpi::accounts::RequestRandom {
             requester: ctx.accounts.requester.to_account_info(),
-            authority: coin_acc.to_account_info(),
-            new_authority: ctx.accounts.initiator.to_account_info(),
+            vault: ctx.accounts.oracle_vault.clone(),
+            authority: ctx.accounts.coin.to_account_info(),
+            oracle: ctx.accounts.oracle.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
         };
 
-        let (_coin_authority, coin_bump) = Pubkey::find_program_address(
-            &[COIN_PREFIX.as_bytes(), ctx.accounts.initiator.key.as_ref()],
-            &ctx.program_id,
-        );
+        let nonce_bytes = ctx.accounts.vault.nonce.to_le_bytes();
+        let (_coin_authority, coin_bump) =
+            coin_pda(ctx.accounts.initiator.key, ctx.accounts.vault.nonce);
 
         let coin_seeds = &[
             COIN_PREFIX.as_bytes(),
             ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
             &[coin_bump],
         ];
 
         let signer = &[&coin_seeds[..]];
 
         let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.degenrand_program.clone(),
+            ctx.accounts.degenrand_program.to_account_info(),
             cpi_accounts,
             signer,
         );
 
-        degenrand::cpi::transfer_authority(cpi_context)?;
+        degenrand::cpi::request_random(cpi_context)?;
 
-        **ctx
-            .accounts
-            .initiator
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() +=
-            ctx.accounts.vault.to_account_info().lamports() + coin_acc.to_account_info().lamports();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(
+            &ctx.accounts.acceptor.key(),
+            &ctx.accounts.oracle_vault.key(),
+            ORACLE_FEE,
+        );
+
+        anchor_lang::solana_program::program::invoke(
+            &ix,
+            &[
+                ctx.accounts.acceptor.to_account_info(),
+                ctx.accounts.oracle_vault.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    pub fn refund_expired<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, RefundExpired<'info>>,
+    ) -> Result<()> {
+        let (is_p2p, is_sol, acceptor_key, acceptor_ata_key) = {
+            let coin = ctx.accounts.coin.load()?;
+
+            // Coins without a request in flight are refunded through cancel_coin
+            if !coin.is_flipping {
+                return Err(DegenErrorCode::CoinNotFlipping.into());
+            }
+
+            if coin.initiator_ata != ctx.accounts.initiator_ata.key() {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            let authority = ctx.accounts.authority.key();
+
+            if authority != coin.initiator && !(coin.is_p2p && authority == coin.acceptor) {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            (coin.is_p2p, coin.is_sol, coin.acceptor, coin.acceptor_ata)
+        };
+
+        let reserved = ctx.accounts.vault.payout;
+        ctx.accounts.treasury.release(reserved);
+
+        let nonce = ctx.accounts.vault.nonce;
+        let nonce_bytes = nonce.to_le_bytes();
+        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key, nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        // Unlock the requester, the oracle fee goes back to whoever paid it
+        let fee_recipient = if is_p2p {
+            let acceptor = ctx
+                .remaining_accounts
+                .get(0)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+
+            if acceptor.key() != acceptor_key {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            acceptor.to_account_info()
+        } else {
+            ctx.accounts.initiator.to_account_info()
+        };
+
+        degenrand::cpi::expire_request(
+            ctx.accounts
+                .expire_request_ctx(fee_recipient)
+                .with_signer(&[coin_seeds]),
+            ctx.accounts.config.refund_timeout,
+        )?;
+
+        let (_, vault_bump) = vault_pda(
+            &ctx.accounts.vault.coin_info.mint_token,
+            ctx.accounts.initiator.key,
+            nonce,
+        );
+
+        let vault_seeds = &[
+            VAULT_PREFIX.as_bytes(),
+            ctx.accounts.vault.coin_info.mint_token.as_ref(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            ctx.program_id.as_ref(),
+            &[vault_bump],
+        ];
+
+        // Delegated stakes were escrowed with the request, both stakes are released alike
+        if is_p2p {
+            let acceptor_ata = ctx
+                .remaining_accounts
+                .get(1)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+
+            if acceptor_ata.key() != acceptor_ata_key {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            anchor_spl::token::transfer(
+                ctx.accounts
+                    .token_release_ctx(acceptor_ata.to_account_info())
+                    .with_signer(&[vault_seeds]),
+                ctx.accounts.vault.coin_info.amount,
+            )?;
+        }
+
+        if !is_sol {
+            anchor_spl::token::transfer(
+                ctx.accounts
+                    .token_release_ctx(ctx.accounts.initiator_ata.to_account_info())
+                    .with_signer(&[vault_seeds]),
+                ctx.accounts.vault.coin_info.amount,
+            )?;
+
+            anchor_spl::token::close_account(
+                ctx.accounts
+                    .token_close_escrow_ctx()
+                    .with_signer(&[vault_seeds]),
+            )?;
+        } else {
+            // The lamports staked on a SOL coin go to the initiator, the vault rent to the recipient
+            let vault = ctx.accounts.vault.to_account_info();
+            let amount = ctx.accounts.vault.coin_info.amount;
+
+            **vault.try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.initiator.try_borrow_mut_lamports()? += amount;
+        }
+
+        degenrand::cpi::remove_pdas(
+            ctx.accounts
+                .coin_remove_requester_ctx()
+                .with_signer(&[coin_seeds]),
+        )?;
+
+        Ok(())
+    }
+
+    pub fn reveal_coin<'key, 'accounts, 'remaining, 'info>(
+        ctx: Context<'key, 'accounts, 'remaining, 'info, RevealCoin<'info>>,
+    ) -> Result<()> {
+        let nonce = ctx.accounts.vault.nonce;
+        let nonce_bytes = nonce.to_le_bytes();
+        let coin_loader = &ctx.accounts.coin;
+
+        let (is_p2p, side, acceptor_key, acceptor_ata_key, referrer_key) = {
+            let coin = coin_loader.load()?;
+
+            if !coin.is_flipping {
+                return Err(DegenErrorCode::CoinNotFlipping.into());
+            }
+
+            // Dice are settled by reveal_dice at the odds of their target
+            if coin.dice_target != 0 {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            (
+                coin.is_p2p,
+                coin.side,
+                coin.acceptor,
+                coin.acceptor_ata,
+                coin.referrer,
+            )
+        };
+
+        // The referrer's token account follows every other remaining account
+        let referrer_ata = if referrer_key == Pubkey::default() {
+            None
+        } else {
+            let referrer_ata = ctx
+                .remaining_accounts
+                .get(if is_p2p { 3 } else { 0 })
+                .ok_or(DegenErrorCode::InvalidReferrer)?;
+
+            if referrer_ata.key() != referrer_key {
+                return Err(DegenErrorCode::InvalidReferrer.into());
+            }
+
+            // A referrer closing or freezing their account after creation must not block the
+            // coin, their share then goes to the fee recipient with the rest of the fee
+            let payable = Account::<TokenAccount>::try_from(referrer_ata)
+                .map(|account| {
+                    account.mint == ctx.accounts.vault.coin_info.mint_token && !account.is_frozen()
+                })
+                .unwrap_or(false);
+
+            if payable {
+                Some(referrer_ata.to_account_info())
+            } else {
+                None
+            }
+        };
+
+        // The acceptor keeps a tally of their own, opened when they accepted the coin
+        let mut acceptor_stats = if is_p2p {
+            let acceptor_stats: Account<PlayerStats> = Account::try_from(
+                ctx.remaining_accounts
+                    .get(2)
+                    .ok_or(DegenErrorCode::Unauthorized)?,
+            )?;
+
+            let (expected_stats, _) =
+                player_stats_pda(&acceptor_key, &ctx.accounts.vault.coin_info.mint_token);
+
+            if acceptor_stats.key() != expected_stats {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            Some(acceptor_stats)
+        } else {
+            None
+        };
+
+        let acceptor_ata = if is_p2p {
+            let acceptor = ctx
+                .remaining_accounts
+                .get(0)
+                .ok_or(DegenErrorCode::Unauthorized)?;
+            let acceptor_ata: Account<TokenAccount> = Account::try_from(
+                ctx.remaining_accounts
+                    .get(1)
+                    .ok_or(DegenErrorCode::Unauthorized)?,
+            )?;
+
+            if acceptor.key() != acceptor_key
+                || acceptor_ata.key() != acceptor_ata_key
+                || acceptor_ata.mint != ctx.accounts.vault.coin_info.mint_token
+            {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            Some(acceptor_ata)
+        } else {
+            None
+        };
+
+        // Determine winner from random number
+        {
+            let requester_loader: AccountLoader<degenrand::Requester> =
+                AccountLoader::try_from_unchecked(ctx.program_id, &ctx.accounts.requester)?;
+
+            let requester = requester_loader.load()?;
+
+            if requester.active_request {
+                return Err(DegenErrorCode::OracleNotCompleted.into());
+            }
+
+            let coin_info = &ctx.accounts.vault.coin_info;
+
+            // P2P coins are a fair toss, house coins use the amount based tiers
+            // P2P coins are a fair toss, house coins are played at the odds locked at creation
+            let win_bps = ctx.accounts.vault.win_bps;
+            let (win_bps, status) = if is_p2p {
+                (MAX_BPS / 2, toss(&requester.random) == side)
+            } else {
+                (win_bps, flip_won(win_bps, &requester.random))
+            };
+
+            let landed = landed_side(side, status);
+
+            // The house pays winners the payout it reserved for the coin, released either way
+            let reserved = ctx.accounts.vault.payout;
+            let payout = if status { reserved } else { 0 };
+
+            ctx.accounts.treasury.release(reserved);
+
+            let (fee, referral_fee) = calculate_fees(
+                coin_info.amount,
+                ctx.accounts.config.fee_bps,
+                ctx.accounts.config.referral_bps,
+                referrer_ata.is_some(),
+            );
+
+            // A slice of the stake grows the pool, which is paid in full on a hit to the initiator
+            // of a house coin or to the winning side of a P2P coin
+            let jackpot = &ctx.accounts.jackpot;
+            let contribution = calculate_contribution(coin_info.amount, jackpot.contribution_bps)
+                .min(coin_info.amount - fee - referral_fee);
+
+            let jackpot_won = if jackpot_hit(&requester.random, jackpot.odds_ppm) {
+                ctx.accounts.jackpot_token.amount + contribution
+            } else {
+                0
+            };
+
+            let settled = coin_info.amount - fee - referral_fee - contribution;
+
+            {
+                let clock: Clock = Clock::get()?;
+                let receipt = &mut ctx.accounts.receipt;
+
+                receipt.coin = coin_loader.key();
+                receipt.initiator = ctx.accounts.initiator.key();
+                receipt.acceptor = acceptor_key;
+                receipt.mint = coin_info.mint_token;
+                receipt.amount = coin_info.amount;
+                receipt.nonce = nonce;
+                receipt.win_bps = win_bps;
+                receipt.random = requester.random;
+                receipt.initiator_won = status;
+                receipt.side = side;
+                receipt.landed = landed;
+                receipt.payout = payout;
+                receipt.fee = fee;
+                receipt.referral_fee = referral_fee;
+                receipt.jackpot_contribution = contribution;
+                receipt.jackpot_won = jackpot_won;
+                receipt.slot = clock.slot;
+                receipt.timestamp = clock.unix_timestamp;
+                receipt.bump = *ctx.bumps.get("receipt").unwrap();
+
+                // P2P winners take the pot less fees, house winners the payout of the treasury
+                let winnings = match (status, is_p2p) {
+                    (false, _) => 0,
+                    (true, true) => settled,
+                    (true, false) => payout,
+                };
+
+                let initiator_jackpot = if is_p2p && !status { 0 } else { jackpot_won };
+
+                ctx.accounts.player_stats.record_flip(
+                    coin_info.amount,
+                    status,
+                    winnings + initiator_jackpot,
+                    clock.unix_timestamp,
+                );
+
+                if let Some(acceptor_stats) = &mut acceptor_stats {
+                    acceptor_stats.record_flip(
+                        coin_info.amount,
+                        !status,
+                        if status { 0 } else { settled + jackpot_won },
+                        clock.unix_timestamp,
+                    );
+                    acceptor_stats.exit(ctx.program_id)?;
+                }
+
+                emit!(CoinRevealed {
+                    coin: coin_loader.key(),
+                    initiator: ctx.accounts.initiator.key(),
+                    acceptor: acceptor_key,
+                    mint: coin_info.mint_token,
+                    amount: coin_info.amount,
+                    initiator_won: status,
+                    win_bps,
+                    payout: winnings,
+                    random_digest: hash(&requester.random).to_bytes(),
+                });
+            }
+
+            let (_, vault_bump) = vault_pda(
+                &ctx.accounts.vault.coin_info.mint_token,
+                ctx.accounts.initiator.key,
+                nonce,
+            );
+
+            let signer_seeds = &[
+                VAULT_PREFIX.as_bytes(),
+                ctx.accounts.vault.coin_info.mint_token.as_ref(),
+                ctx.accounts.initiator.key.as_ref(),
+                &nonce_bytes,
+                ctx.program_id.as_ref(),
+                &[vault_bump],
+            ];
+
+            let mut jackpot_ata = ctx.accounts.initiator_ata.to_account_info();
+
+            if let Some(acceptor_ata) = &acceptor_ata {
+                let winner_ata = if status {
+                    ctx.accounts.initiator_ata.to_account_info()
+                } else {
+                    acceptor_ata.to_account_info()
+                };
+
+                let stake = ctx.accounts.escrow.to_account_info();
+
+                ctx.accounts.pay_fees(
+                    stake.clone(),
+                    referrer_ata,
+                    fee,
+                    referral_fee,
+                    contribution,
+                    &[signer_seeds],
+                )?;
+
+                jackpot_ata = winner_ata.clone();
+
+                // The winning side gets the pot less fees
+                anchor_spl::token::transfer(
+                    ctx.accounts
+                        .token_transfer_ctx(ctx.accounts.vault.to_account_info(), stake, winner_ata)
+                        .with_signer(&[signer_seeds]),
+                    coin_info.amount + settled,
+                )?;
+            } else {
+                let stake = ctx.accounts.escrow.to_account_info();
+
+                // Fees and the jackpot slice are charged on the stake whatever the outcome
+                ctx.accounts.pay_fees(
+                    stake.clone(),
+                    referrer_ata,
+                    fee,
+                    referral_fee,
+                    contribution,
+                    &[signer_seeds],
+                )?;
+
+                // Pay out Token B from the prize vault. The reward is capped to what the vault
+                // holds so an underfunded vault can never leave the stake stuck in escrow.
+                if status {
+                    // The stake less fees is released back to the initiator
+                    anchor_spl::token::transfer(
+                        ctx.accounts
+                            .token_transfer_ctx(
+                                ctx.accounts.vault.to_account_info(),
+                                stake,
+                                ctx.accounts.initiator_ata.to_account_info(),
+                            )
+                            .with_signer(&[signer_seeds]),
+                        settled,
+                    )?;
+
+                    let treasury = &ctx.accounts.treasury;
+
+                    let treasury_seeds = &[
+                        TREASURY_PREFIX.as_bytes(),
+                        treasury.mint.as_ref(),
+                        &[treasury.bump],
+                    ];
+
+                    anchor_spl::token::transfer(
+                        ctx.accounts
+                            .token_transfer_ctx(
+                                treasury.to_account_info(),
+                                ctx.accounts.treasury_token.to_account_info(),
+                                ctx.accounts.initiator_ata.to_account_info(),
+                            )
+                            .with_signer(&[treasury_seeds]),
+                        payout,
+                    )?;
+
+                    let prize_vault = &ctx.accounts.prize_vault;
+                    let reward = prize_vault.reward.min(ctx.accounts.prize_token.amount);
+
+                    let prize_seeds = &[
+                        PRIZE_VAULT_PREFIX.as_bytes(),
+                        prize_vault.prize_mint.as_ref(),
+                        &[prize_vault.bump],
+                    ];
+
+                    anchor_spl::token::transfer(
+                        ctx.accounts
+                            .token_transfer_ctx(
+                                prize_vault.to_account_info(),
+                                ctx.accounts.prize_token.to_account_info(),
+                                ctx.accounts.initiator_prize_ata.to_account_info(),
+                            )
+                            .with_signer(&[prize_seeds]),
+                        reward,
+                    )?;
+                } else {
+                    // The rest of the lost stake joins the bankroll
+                    anchor_spl::token::transfer(
+                        ctx.accounts
+                            .token_transfer_ctx(
+                                ctx.accounts.vault.to_account_info(),
+                                stake,
+                                ctx.accounts.treasury_token.to_account_info(),
+                            )
+                            .with_signer(&[signer_seeds]),
+                        settled,
+                    )?;
+                }
+            }
+
+            if jackpot_won > 0 {
+                let jackpot = &ctx.accounts.jackpot;
+
+                let jackpot_seeds = &[
+                    JACKPOT_PREFIX.as_bytes(),
+                    jackpot.mint.as_ref(),
+                    &[jackpot.bump],
+                ];
+
+                anchor_spl::token::transfer(
+                    ctx.accounts
+                        .token_transfer_ctx(
+                            jackpot.to_account_info(),
+                            ctx.accounts.jackpot_token.to_account_info(),
+                            jackpot_ata,
+                        )
+                        .with_signer(&[jackpot_seeds]),
+                    jackpot_won,
+                )?;
+            }
+
+            // The escrow is emptied by now, its rent goes to the recipient
+            anchor_spl::token::close_account(
+                ctx.accounts
+                    .token_close_escrow_ctx()
+                    .with_signer(&[signer_seeds]),
+            )?;
+        }
+
+        // Close the requester of the coin, its rent is swept to the recipient with the coin
+        let coin_acc = ctx.accounts.coin.to_account_info();
+
+        let cpi_accounts = degenrand::cpi::accounts::Cleanup {
+            authority: coin_acc.to_account_info(),
+            requester: ctx.accounts.requester.to_account_info(),
+            initiator: coin_acc.to_account_info(),
+            vault: ctx.accounts.oracle_vault.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+
+        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key, nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        let signer = &[&coin_seeds[..]];
+
+        let cpi_context = CpiContext::new_with_signer(
+            ctx.accounts.degenrand_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        degenrand::cpi::remove_pdas(cpi_context)?;
+
+        // A keeper cranking the coin is paid out of the rent returned with it, together with the
+        // receipt rent they fronted, which the initiator bears
+        if ctx.accounts.authority.key() != ctx.accounts.initiator.key() {
+            let receipt_rent = ctx.accounts.receipt.to_account_info().lamports();
+
+            pay_keeper(
+                &ctx.accounts.coin.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                receipt_rent + ctx.accounts.config.keeper_reward,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn reveal_sol_coin(ctx: Context<RevealSolCoin>) -> Result<()> {
+        {
+            let coin = ctx.accounts.coin.load()?;
+
+            if !coin.is_sol {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            if !coin.is_flipping {
+                return Err(DegenErrorCode::CoinNotFlipping.into());
+            }
+        }
+
+        let nonce = ctx.accounts.vault.nonce;
+        let nonce_bytes = nonce.to_le_bytes();
+        let amount = ctx.accounts.vault.coin_info.amount;
+
+        let (status, payout) = {
+            let requester_loader: AccountLoader<degenrand::Requester> =
+                AccountLoader::try_from_unchecked(ctx.program_id, &ctx.accounts.requester)?;
+
+            let requester = requester_loader.load()?;
+
+            if requester.active_request {
+                return Err(DegenErrorCode::OracleNotCompleted.into());
+            }
+
+            // Played at the odds locked at creation, the reserved payout is released either way
+            let win_bps = ctx.accounts.vault.win_bps;
+            let status = flip_won(win_bps, &requester.random);
+            let side = ctx.accounts.coin.load()?.side;
+
+            let reserved = ctx.accounts.vault.payout;
+            let payout = if status { reserved } else { 0 };
+
+            ctx.accounts.treasury.release(reserved);
+
+            let clock: Clock = Clock::get()?;
+            let receipt = &mut ctx.accounts.receipt;
+
+            receipt.coin = ctx.accounts.coin.key();
+            receipt.initiator = ctx.accounts.initiator.key();
+            receipt.acceptor = Pubkey::default();
+            receipt.mint = spl_token::native_mint::id();
+            receipt.amount = amount;
+            receipt.nonce = nonce;
+            receipt.win_bps = win_bps;
+            receipt.random = requester.random;
+            receipt.initiator_won = status;
+            receipt.side = side;
+            receipt.landed = landed_side(side, status);
+            receipt.payout = payout;
+            receipt.slot = clock.slot;
+            receipt.timestamp = clock.unix_timestamp;
+            receipt.bump = *ctx.bumps.get("receipt").unwrap();
+
+            ctx.accounts
+                .player_stats
+                .record_flip(amount, status, payout, clock.unix_timestamp);
+
+            emit!(CoinRevealed {
+                coin: ctx.accounts.coin.key(),
+                initiator: ctx.accounts.initiator.key(),
+                acceptor: Pubkey::default(),
+                mint: spl_token::native_mint::id(),
+                amount,
+                initiator_won: status,
+                win_bps,
+                payout,
+                random_digest: hash(&requester.random).to_bytes(),
+            });
+
+            (status, payout)
+        };
+
+        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key, nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        degenrand::cpi::remove_pdas(
+            ctx.accounts
+                .coin_remove_requester_ctx()
+                .with_signer(&[coin_seeds]),
+        )?;
+
+        // A keeper cranking the coin is paid out of the rent returned with it, together with the
+        // receipt rent they fronted, which the initiator bears
+        if ctx.accounts.authority.key() != ctx.accounts.initiator.key() {
+            let receipt_rent = ctx.accounts.receipt.to_account_info().lamports();
+
+            pay_keeper(
+                &ctx.accounts.coin.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                receipt_rent + ctx.accounts.config.keeper_reward,
+            )?;
+        }
+
+        // A win returns the stake to the initiator with the payout, the vault rent goes to the
+        // recipient
+        let (from, to, lamports) = if status {
+            let vault = ctx.accounts.vault.to_account_info();
+
+            **vault.try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.initiator.try_borrow_mut_lamports()? += amount;
+
+            (
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.initiator.to_account_info(),
+                payout,
+            )
+        } else {
+            (
+                ctx.accounts.vault.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                amount,
+            )
+        };
+
+        **from.try_borrow_mut_lamports()?.deref_mut() -= lamports;
+        **to.try_borrow_mut_lamports()?.deref_mut() += lamports;
+
+        Ok(())
+    }
+
+    /**
+     * Settles a dice against the treasury, a roll under the target wins the fair payout of its
+     * odds less the house edge
+     */
+    pub fn reveal_dice(ctx: Context<RevealDice>) -> Result<()> {
+        let target = {
+            let coin = ctx.accounts.coin.load()?;
+
+            if coin.dice_target == 0 {
+                return Err(DegenErrorCode::NotDice.into());
+            }
+
+            if !coin.is_flipping {
+                return Err(DegenErrorCode::CoinNotFlipping.into());
+            }
+
+            if coin.initiator_ata != ctx.accounts.initiator_ata.key() {
+                return Err(DegenErrorCode::Unauthorized.into());
+            }
+
+            coin.dice_target
+        };
+
+        let nonce = ctx.accounts.vault.nonce;
+        let nonce_bytes = nonce.to_le_bytes();
+        let amount = ctx.accounts.vault.coin_info.amount;
+
+        let (status, payout) = {
+            let requester_loader: AccountLoader<degenrand::Requester> =
+                AccountLoader::try_from_unchecked(ctx.program_id, &ctx.accounts.requester)?;
+
+            let requester = requester_loader.load()?;
+
+            if requester.active_request {
+                return Err(DegenErrorCode::OracleNotCompleted.into());
+            }
+
+            let status = roll_dice(&requester.random) < target;
+
+            // The payout reserved at creation is paid on a win, released either way
+            let reserved = ctx.accounts.vault.payout;
+            let payout = if status { reserved } else { 0 };
+
+            ctx.accounts.treasury.release(reserved);
+
+            let win_bps = ctx.accounts.vault.win_bps;
+            let clock: Clock = Clock::get()?;
+            let receipt = &mut ctx.accounts.receipt;
+
+            receipt.coin = ctx.accounts.coin.key();
+            receipt.initiator = ctx.accounts.initiator.key();
+            receipt.acceptor = Pubkey::default();
+            receipt.mint = ctx.accounts.vault.coin_info.mint_token;
+            receipt.amount = amount;
+            receipt.nonce = nonce;
+            receipt.win_bps = win_bps;
+            receipt.dice_target = target;
+            receipt.random = requester.random;
+            receipt.initiator_won = status;
+            receipt.payout = payout;
+            receipt.slot = clock.slot;
+            receipt.timestamp = clock.unix_timestamp;
+            receipt.bump = *ctx.bumps.get("receipt").unwrap();
+
+            ctx.accounts
+                .player_stats
+                .record_flip(amount, status, payout, clock.unix_timestamp);
+
+            emit!(CoinRevealed {
+                coin: ctx.accounts.coin.key(),
+                initiator: ctx.accounts.initiator.key(),
+                acceptor: Pubkey::default(),
+                mint: ctx.accounts.vault.coin_info.mint_token,
+                amount,
+                initiator_won: status,
+                win_bps,
+                payout,
+                random_digest: hash(&requester.random).to_bytes(),
+            });
+
+            (status, payout)
+        };
+
+        let (_, vault_bump) = vault_pda(
+            &ctx.accounts.vault.coin_info.mint_token,
+            ctx.accounts.initiator.key,
+            nonce,
+        );
+
+        let vault_seeds = &[
+            VAULT_PREFIX.as_bytes(),
+            ctx.accounts.vault.coin_info.mint_token.as_ref(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            ctx.program_id.as_ref(),
+            &[vault_bump],
+        ];
+
+        // The stake goes back to a winner, to the bankroll otherwise
+        let stake_recipient = if status {
+            ctx.accounts.initiator_ata.to_account_info()
+        } else {
+            ctx.accounts.treasury_token.to_account_info()
+        };
+
+        anchor_spl::token::transfer(
+            ctx.accounts
+                .token_transfer_ctx(
+                    ctx.accounts.vault.to_account_info(),
+                    ctx.accounts.escrow.to_account_info(),
+                    stake_recipient,
+                )
+                .with_signer(&[vault_seeds]),
+            amount,
+        )?;
+
+        if status {
+            let treasury = &ctx.accounts.treasury;
+
+            let treasury_seeds = &[
+                TREASURY_PREFIX.as_bytes(),
+                treasury.mint.as_ref(),
+                &[treasury.bump],
+            ];
+
+            anchor_spl::token::transfer(
+                ctx.accounts
+                    .token_transfer_ctx(
+                        treasury.to_account_info(),
+                        ctx.accounts.treasury_token.to_account_info(),
+                        ctx.accounts.initiator_ata.to_account_info(),
+                    )
+                    .with_signer(&[treasury_seeds]),
+                payout,
+            )?;
+        }
+
+        anchor_spl::token::close_account(
+            ctx.accounts
+                .token_close_escrow_ctx()
+                .with_signer(&[vault_seeds]),
+        )?;
+
+        let (_coin_authority, coin_bump) = coin_pda(ctx.accounts.initiator.key, nonce);
+
+        let coin_seeds = &[
+            COIN_PREFIX.as_bytes(),
+            ctx.accounts.initiator.key.as_ref(),
+            &nonce_bytes,
+            &[coin_bump],
+        ];
+
+        degenrand::cpi::remove_pdas(
+            ctx.accounts
+                .coin_remove_requester_ctx()
+                .with_signer(&[coin_seeds]),
+        )?;
+
+        // A keeper cranking the coin is paid out of the rent returned with it, together with the
+        // receipt rent they fronted, which the initiator bears
+        if ctx.accounts.authority.key() != ctx.accounts.initiator.key() {
+            let receipt_rent = ctx.accounts.receipt.to_account_info().lamports();
+
+            pay_keeper(
+                &ctx.accounts.coin.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                receipt_rent + ctx.accounts.config.keeper_reward,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Closes what is left of a completed coin, its receipt, returning the rent to a recipient
+     * picked by the initiator. Receipts are kept as an audit trail for `RECEIPT_RETENTION`
+     * seconds after the reveal first.
+     */
+    pub fn close_coin(ctx: Context<CloseCoin>) -> Result<()> {
+        let clock: Clock = Clock::get()?;
+
+        if clock.unix_timestamp < ctx.accounts.receipt.timestamp + RECEIPT_RETENTION {
+            return Err(DegenErrorCode::ReceiptRetained.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateCoin<'info> {
+    /// Numbers the coin, its count is the nonce of the new coin
+    #[account(
+        mut,
+        seeds = [COUNTER_PREFIX.as_bytes(), initiator.key().as_ref()],
+        bump = counter.bump,
+    )]
+    pub counter: Account<'info, CoinCounter>,
+
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &counter.count.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<Coin>(),
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        init,
+        seeds = [VAULT_PREFIX.as_bytes(), mint.key().as_ref(), initiator.key().as_ref(), &counter.count.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        payer = initiator,
+        space = 8 + size_of::<Vault>()
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA for calling the Oracle for random number, initialized for the coin at its address
+    #[account(
+        mut,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle, initialized for the coin
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// CHECK: Oracle answering the request of the coin, set by the admin
+    #[account(address = config.oracle @ DegenErrorCode::InvalidOracle)]
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: Initiator of the flip
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// Initiator Token A ATA the stake is drawn from
+    #[account(
+        mut,
+        constraint = initiator_ata.mint == mint.key() @ DegenErrorCode::InvalidMint,
+    )]
+    pub initiator_ata: Account<'info, TokenAccount>,
+
+    /// Token A mint, SOL is staked through create_sol_coin
+    #[account(
+        constraint = mint.key() != spl_token::native_mint::id() @ DegenErrorCode::NativeMint,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Token A account owned by the vault, escrowing the stakes of the coin
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    /// Turns new coins away while the game is paused
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @ DegenErrorCode::Paused,
+        constraint = config.fee_recipient(&mint.key()).is_some() @ DegenErrorCode::InvalidFeeRecipient,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// Allows the mint to be staked, only when enabled by the admin
+    #[account(
+        seeds = [MINT_CONFIG_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.enabled @ DegenErrorCode::MintDisabled,
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    /// Odds of the mint, which the coin is locked at
+    #[account(
+        address = mint_config.tier_table @ DegenErrorCode::InvalidTierTable,
+    )]
+    pub tier_table: Box<Account<'info, TierTable>>,
+
+    /// House bankroll of the mint, reserving the payout of a house coin until the reveal
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// Token A account escrowed by the treasury
+    #[account(
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+
+    /// Pool of the mint, which the reveal feeds with the stake
+    #[account(
+        seeds = [JACKPOT_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    /// PDA holding the Token B reward the coin is played for.
+    #[account(
+        constraint = prize_vault.prize_mint == mint_config.prize_mint @ DegenErrorCode::InvalidPrizeAccount,
+    )]
+    pub prize_vault: Box<Account<'info, PrizeVault>>,
+
+    /// Statistics of the initiator in the staked mint, opened on their first coin
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<PlayerStats>(),
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    /// CHECK: System Variable for getting rent to create a PDA.
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateCoin<'info> {
+    pub fn requester_initialize_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Initialize<'info>> {
+        // The Coin PDA owns the requester, the initiator pays for it
+        let cpi_accounts = degenrand::cpi::accounts::Initialize {
+            requester: self.requester.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            authority: self.coin.to_account_info(),
+            payer: self.initiator.to_account_info(),
+            oracle: self.oracle.to_account_info(),
+            rent: self.rent.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_approve_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Approve<'info>> {
+        let cpi_accounts = Approve {
+            delegate: self.vault.to_account_info(),
+            authority: self.initiator.to_account_info(),
+            to: self.initiator_ata.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_escrow_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.initiator_ata.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.initiator.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateSolCoin<'info> {
+    /// Numbers the coin, its count is the nonce of the new coin
+    #[account(
+        mut,
+        seeds = [COUNTER_PREFIX.as_bytes(), initiator.key().as_ref()],
+        bump = counter.bump,
+    )]
+    pub counter: Account<'info, CoinCounter>,
+
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &counter.count.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<Coin>(),
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    /// Escrows the staked lamports
+    #[account(
+        init,
+        seeds = [VAULT_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref(), initiator.key().as_ref(), &counter.count.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        payer = initiator,
+        space = 8 + size_of::<Vault>()
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA for calling the Oracle for random number, initialized for the coin at its address
+    #[account(
+        mut,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle, initialized for the coin
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// CHECK: Oracle answering the request of the coin, set by the admin
+    #[account(address = config.oracle @ DegenErrorCode::InvalidOracle)]
+    pub oracle: AccountInfo<'info>,
+
+    /// Initiator of the flip, paying the stake
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// Turns new coins away while the game is paused
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @ DegenErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// Allows SOL to be staked, only when enabled by the admin
+    #[account(
+        seeds = [MINT_CONFIG_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.enabled @ DegenErrorCode::MintDisabled,
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    /// Odds of the mint, which the coin is locked at
+    #[account(
+        address = mint_config.tier_table @ DegenErrorCode::InvalidTierTable,
+    )]
+    pub tier_table: Box<Account<'info, TierTable>>,
+
+    /// SOL bankroll, reserving the payout of the coin until the reveal
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// Statistics of the initiator in SOL, opened on their first coin
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), spl_token::native_mint::id().as_ref()],
+        bump,
+        space = 8 + size_of::<PlayerStats>(),
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateSolCoin<'info> {
+    pub fn requester_initialize_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Initialize<'info>> {
+        // The Coin PDA owns the requester, the initiator pays for it
+        let cpi_accounts = degenrand::cpi::accounts::Initialize {
+            requester: self.requester.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            authority: self.coin.to_account_info(),
+            payer: self.initiator.to_account_info(),
+            oracle: self.oracle.to_account_info(),
+            rent: self.rent.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitCounter<'info> {
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [COUNTER_PREFIX.as_bytes(), initiator.key().as_ref()],
+        bump,
+        space = 8 + size_of::<CoinCounter>(),
+    )]
+    pub counter: Account<'info, CoinCounter>,
+
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDice<'info> {
+    /// Numbers the dice, sharing the count of the coins of the initiator
+    #[account(
+        mut,
+        seeds = [COUNTER_PREFIX.as_bytes(), initiator.key().as_ref()],
+        bump = counter.bump,
+    )]
+    pub counter: Account<'info, CoinCounter>,
+
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &counter.count.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<Coin>(),
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        init,
+        seeds = [VAULT_PREFIX.as_bytes(), mint.key().as_ref(), initiator.key().as_ref(), &counter.count.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        payer = initiator,
+        space = 8 + size_of::<Vault>()
+    )]
+    pub vault: Account<'info, Vault>,
+
+    /// CHECK: PDA for calling the Oracle for random number, initialized for the dice at its address
+    #[account(
+        mut,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle, initialized for the dice
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// CHECK: Oracle answering the request of the coin, set by the admin
+    #[account(address = config.oracle @ DegenErrorCode::InvalidOracle)]
+    pub oracle: AccountInfo<'info>,
+
+    /// Initiator of the roll
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// Initiator Token A ATA the stake is drawn from
+    #[account(
+        mut,
+        constraint = initiator_ata.mint == mint.key() @ DegenErrorCode::InvalidMint,
+    )]
+    pub initiator_ata: Account<'info, TokenAccount>,
+
+    /// Token A mint, SOL is staked through create_sol_coin
+    #[account(
+        constraint = mint.key() != spl_token::native_mint::id() @ DegenErrorCode::NativeMint,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// Token A account owned by the vault, escrowing the stakes of the coin
+    #[account(
+        init,
+        payer = initiator,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    /// Turns new dice away while the game is paused
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @ DegenErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// Allows the mint to be staked, only when enabled by the admin
+    #[account(
+        seeds = [MINT_CONFIG_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = mint_config.bump,
+        constraint = mint_config.enabled @ DegenErrorCode::MintDisabled,
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    /// House bankroll of the mint, reserving the payout of the dice until the reveal
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// Token A account escrowed by the treasury
+    #[account(
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+
+    /// Statistics of the initiator in the staked mint, opened on their first coin
+    #[account(
+        init_if_needed,
+        payer = initiator,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<PlayerStats>(),
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CreateDice<'info> {
+    pub fn requester_initialize_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Initialize<'info>> {
+        let cpi_accounts = degenrand::cpi::accounts::Initialize {
+            requester: self.requester.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            authority: self.coin.to_account_info(),
+            payer: self.initiator.to_account_info(),
+            oracle: self.oracle.to_account_info(),
+            rent: self.rent.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_escrow_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.initiator_ata.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.initiator.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct FlipCoin<'info> {
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// Initiator of the flip
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// CHECK: Account making the random request
+    #[account(mut)]
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// Holds flips back while the game is paused
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @ DegenErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCoin<'info> {
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        close = recipient,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        close = recipient,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// Initiator of the flip
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+
+    /// CHECK: Any account chosen by the initiator to receive the rent of the closed accounts
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// CHECK: Initiator Token A ATA the stake is returned to, the initiator itself for SOL coins.
+    /// Matched against the coin.
+    #[account(mut)]
+    pub initiator_ata: AccountInfo<'info>,
+
+    /// CHECK: Token A account owned by the vault escrowing the stake, never created for SOL coins
+    #[account(
+        mut,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// House bankroll of the staked mint, releasing the payout reserved for the coin
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CancelCoin<'info> {
+    pub fn coin_remove_requester_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Cleanup<'info>> {
+        // Close the requester of the coin into the coin itself
+        let cpi_accounts = degenrand::cpi::accounts::Cleanup {
+            authority: self.coin.to_account_info(),
+            requester: self.requester.to_account_info(),
+            initiator: self.coin.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_release_ctx<'b, 'c>(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.escrow.to_account_info(),
+            to,
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_close_escrow_ctx<'b, 'c>(
+        &self,
+    ) -> CpiContext<'_, 'b, 'c, 'info, CloseAccount<'info>> {
+        // The initiator paid the rent of the escrow, it goes to the recipient they picked
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RefundExpired<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        close = recipient,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        close = recipient,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand escrowing the Oracle fee
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// CHECK: The account creating the flip
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Receives the rent of the closed accounts. Only the initiator may pick an account
+    /// other than themselves, whoever settles the coin.
+    #[account(
+        mut,
+        constraint = recipient.key() == initiator.key() || authority.key() == initiator.key() @ DegenErrorCode::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    /// CHECK: Initiator Token A ATA the stake is returned to, the initiator itself for SOL coins.
+    /// Matched against the coin.
+    #[account(mut)]
+    pub initiator_ata: AccountInfo<'info>,
+
+    /// CHECK: Token A account owned by the vault escrowing the stake, never created for SOL coins
+    #[account(
+        mut,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub escrow: AccountInfo<'info>,
+
+    /// House bankroll of the staked mint, releasing the payout reserved for the coin
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// The initiator, or the acceptor of a P2P coin
+    pub authority: Signer<'info>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundExpired<'info> {
+    pub fn expire_request_ctx(
+        &self,
+        recipient: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::ExpireRequest<'info>> {
+        let cpi_accounts = degenrand::cpi::accounts::ExpireRequest {
+            requester: self.requester.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            authority: self.coin.to_account_info(),
+            recipient,
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn coin_remove_requester_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Cleanup<'info>> {
+        // Close the requester of the coin into the coin itself
+        let cpi_accounts = degenrand::cpi::accounts::Cleanup {
+            authority: self.coin.to_account_info(),
+            requester: self.requester.to_account_info(),
+            initiator: self.coin.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_release_ctx<'b, 'c>(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.escrow.to_account_info(),
+            to,
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_close_escrow_ctx<'b, 'c>(
+        &self,
+    ) -> CpiContext<'_, 'b, 'c, 'info, CloseAccount<'info>> {
+        // The initiator paid the rent of the escrow, it goes to the recipient they picked
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct AcceptCoin<'info> {
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    #[account(
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: The account creating the flip
+    pub initiator: AccountInfo<'info>,
+
+    /// The account accepting the offer to flip
+    #[account(mut)]
+    pub acceptor: Signer<'info>,
+
+    /// Acceptor Token A ATA
+    #[account(
+        mut,
+        constraint = acceptor_ata.mint == vault.coin_info.mint_token @ DegenErrorCode::Unauthorized,
+    )]
+    pub acceptor_ata: Box<Account<'info, TokenAccount>>,
+
+    /// Flips of the acceptor in the mint of the coin
+    #[account(
+        init_if_needed,
+        payer = acceptor,
+        seeds = [STATS_PREFIX.as_bytes(), acceptor.key().as_ref(), vault.coin_info.mint_token.as_ref()],
+        bump,
+        space = 8 + size_of::<PlayerStats>(),
+    )]
+    pub acceptor_stats: Box<Account<'info, PlayerStats>>,
+
+    /// Token A account owned by the vault, escrowing the stakes of the coin
+    #[account(
+        mut,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Account making the random request
+    #[account(mut)]
+    pub oracle: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// Holds offers back while the game is paused and names the oracle they must rely on
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = !config.paused @ DegenErrorCode::Paused,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptCoin<'info> {
+    pub fn token_escrow_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            from: self.acceptor_ata.to_account_info(),
+            to: self.escrow.to_account_info(),
+            authority: self.acceptor.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevealCoin<'info> {
+    /// The initiator or a keeper settling the coin, fronts the receipt rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The account creating the flip
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Receives the rent of the closed accounts. Only the initiator may pick an account
+    /// other than themselves, whoever settles the coin.
+    #[account(
+        mut,
+        constraint = recipient.key() == initiator.key() || authority.key() == initiator.key() @ DegenErrorCode::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    /// Initiator Token A ATA recorded on the coin
+    #[account(
+        mut,
+        constraint = initiator_ata.mint == vault.coin_info.mint_token @ DegenErrorCode::InvalidMint,
+    )]
+    pub initiator_ata: Box<Account<'info, TokenAccount>>,
+
+    /// Token A mint staked on the coin
+    #[account(address = vault.coin_info.mint_token @ DegenErrorCode::InvalidMint)]
+    pub mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        has_one = initiator @ DegenErrorCode::Unauthorized,
+        has_one = initiator_ata @ DegenErrorCode::Unauthorized,
+        close = recipient,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    /// Authority of the escrow
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        close = recipient,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// Token A account owned by the vault, escrowing the stakes of the coin
+    #[account(
+        mut,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    /// Outcome of the flip, kept as an audit trail
+    #[account(
+        init,
+        payer = authority,
+        seeds = [RECEIPT_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<FlipReceipt>(),
+    )]
+    pub receipt: Box<Account<'info, FlipReceipt>>,
+
+    /// Statistics of the initiator in the staked mint
+    #[account(
+        mut,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), vault.coin_info.mint_token.as_ref()],
+        bump = player_stats.bump,
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// PDA holding the Token B reward recorded on the vault
+    #[account(
+        seeds = [PRIZE_VAULT_PREFIX.as_bytes(), vault.coin_info.prize_mint.as_ref()],
+        bump = prize_vault.bump,
+    )]
+    pub prize_vault: Box<Account<'info, PrizeVault>>,
+
+    /// Token B account escrowed by the prize vault
+    #[account(
+        mut,
+        address = prize_vault.prize_token @ DegenErrorCode::InvalidPrizeAccount,
+    )]
+    pub prize_token: Box<Account<'info, TokenAccount>>,
+
+    /// Initiator Token B ATA receiving the reward
+    #[account(
+        mut,
+        constraint = initiator_prize_ata.mint == prize_vault.prize_mint @ DegenErrorCode::InvalidPrizeAccount,
+        constraint = initiator_prize_ata.owner == initiator.key() @ DegenErrorCode::InvalidPrizeAccount,
+    )]
+    pub initiator_prize_ata: Box<Account<'info, TokenAccount>>,
+
+    /// House bankroll of the staked mint, collecting losses and paying wins
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// Token A account escrowed by the treasury
+    #[account(
+        mut,
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+
+    /// Pool of the staked mint, grown by every stake
+    #[account(
+        seeds = [JACKPOT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Box<Account<'info, Jackpot>>,
+
+    /// Token A account escrowed by the jackpot
+    #[account(
+        mut,
+        address = jackpot.jackpot_token @ DegenErrorCode::InvalidJackpotAccount,
+    )]
+    pub jackpot_token: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// Token A account registered to collect the protocol fee
+    #[account(
+        mut,
+        constraint = config.fee_recipient(&vault.coin_info.mint_token) == Some(fee_recipient.key()) @ DegenErrorCode::InvalidFeeRecipient,
+    )]
+    pub fee_recipient: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    // pub rent: Sysvar<'info, Rent>,
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealCoin<'info> {
+    /// Moves the protocol fee, the referral fee and the jackpot slice out of an escrowed stake
+    pub fn pay_fees(
+        &self,
+        stake: AccountInfo<'info>,
+        referrer_ata: Option<AccountInfo<'info>>,
+        fee: u64,
+        referral_fee: u64,
+        contribution: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        if contribution > 0 {
+            anchor_spl::token::transfer(
+                self.token_transfer_ctx(
+                    self.vault.to_account_info(),
+                    stake.clone(),
+                    self.jackpot_token.to_account_info(),
+                )
+                .with_signer(signer_seeds),
+                contribution,
+            )?;
+        }
+
+        if fee > 0 {
+            anchor_spl::token::transfer(
+                self.token_transfer_ctx(
+                    self.vault.to_account_info(),
+                    stake.clone(),
+                    self.fee_recipient.to_account_info(),
+                )
+                .with_signer(signer_seeds),
+                fee,
+            )?;
+        }
+
+        if let Some(referrer_ata) = referrer_ata.filter(|_| referral_fee > 0) {
+            anchor_spl::token::transfer(
+                self.token_transfer_ctx(self.vault.to_account_info(), stake, referrer_ata)
+                    .with_signer(signer_seeds),
+                referral_fee,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn token_transfer_ctx(
+        &self,
+        authority: AccountInfo<'info>,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            authority: authority.to_account_info(),
+            from,
+            to,
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_close_escrow_ctx<'b, 'c>(
+        &self,
+    ) -> CpiContext<'_, 'b, 'c, 'info, CloseAccount<'info>> {
+        // The initiator paid the rent of the escrow, it goes to the recipient they picked
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevealSolCoin<'info> {
+    /// The initiator or a keeper settling the coin, fronts the receipt rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The account creating the flip
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Receives the rent of the closed accounts. Only the initiator may pick an account
+    /// other than themselves, whoever settles the coin.
+    #[account(
+        mut,
+        constraint = recipient.key() == initiator.key() || authority.key() == initiator.key() @ DegenErrorCode::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        close = recipient,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    /// Escrows the staked lamports
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        close = recipient,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// Outcome of the flip, kept as an audit trail
+    #[account(
+        init,
+        payer = authority,
+        seeds = [RECEIPT_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<FlipReceipt>(),
+    )]
+    pub receipt: Box<Account<'info, FlipReceipt>>,
+
+    /// Statistics of the initiator in SOL
+    #[account(
+        mut,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), spl_token::native_mint::id().as_ref()],
+        bump = player_stats.bump,
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// SOL bankroll, collecting losses and paying wins
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// Reward of the keeper settling the coin
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealSolCoin<'info> {
+    pub fn coin_remove_requester_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Cleanup<'info>> {
+        // Close the requester of the coin into the coin itself
+        let cpi_accounts = degenrand::cpi::accounts::Cleanup {
+            authority: self.coin.to_account_info(),
+            requester: self.requester.to_account_info(),
+            initiator: self.coin.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct RevealDice<'info> {
+    /// The initiator or a keeper settling the dice, fronts the receipt rent
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: The account creating the dice
+    #[account(mut)]
+    pub initiator: AccountInfo<'info>,
+
+    /// CHECK: Receives the rent of the closed accounts. Only the initiator may pick an account
+    /// other than themselves, whoever settles the coin.
+    #[account(
+        mut,
+        constraint = recipient.key() == initiator.key() || authority.key() == initiator.key() @ DegenErrorCode::InvalidRecipient,
+    )]
+    pub recipient: AccountInfo<'info>,
+
+    /// Initiator Token A ATA recorded on the dice
+    #[account(
+        mut,
+        constraint = initiator_ata.mint == vault.coin_info.mint_token @ DegenErrorCode::InvalidMint,
+    )]
+    pub initiator_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        close = recipient,
+    )]
+    pub coin: AccountLoader<'info, Coin>,
+
+    /// Authority of the escrow
+    #[account(
+        mut,
+        seeds = [VAULT_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref(), initiator.key().as_ref(), &vault.nonce.to_le_bytes(), crate::id().as_ref(),],
+        bump,
+        close = recipient,
+    )]
+    pub vault: Box<Account<'info, Vault>>,
+
+    /// Token A account owned by the vault, escrowing the stake of the dice
+    #[account(
+        mut,
+        seeds = [ESCROW_PREFIX.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Box<Account<'info, TokenAccount>>,
+
+    /// Outcome of the roll, kept as an audit trail
+    #[account(
+        init,
+        payer = authority,
+        seeds = [RECEIPT_PREFIX.as_bytes(), initiator.key().as_ref(), &vault.nonce.to_le_bytes()],
+        bump,
+        space = 8 + size_of::<FlipReceipt>(),
+    )]
+    pub receipt: Box<Account<'info, FlipReceipt>>,
+
+    /// Statistics of the initiator in the staked mint
+    #[account(
+        mut,
+        seeds = [STATS_PREFIX.as_bytes(), initiator.key().as_ref(), vault.coin_info.mint_token.as_ref()],
+        bump = player_stats.bump,
+    )]
+    pub player_stats: Box<Account<'info, PlayerStats>>,
+
+    /// House bankroll of the staked mint, collecting losses and paying wins
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), vault.coin_info.mint_token.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Box<Account<'info, Treasury>>,
+
+    /// Token A account escrowed by the treasury
+    #[account(
+        mut,
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: PDA of degenrand for calling the Oracle for random number, owned by the coin
+    #[account(
+        mut,
+        owner = degenrand::ID @ DegenErrorCode::InvalidRequester,
+        address = degenrand::requestor_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub requester: AccountInfo<'info>,
+
+    /// CHECK: PDA owned by degenrand for paying the Oracle
+    #[account(
+        mut,
+        address = degenrand::vault_pda(&coin.key()).0 @ DegenErrorCode::InvalidRequester,
+    )]
+    pub oracle_vault: AccountInfo<'info>,
+
+    /// Reward of the keeper settling the coin
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, GameConfig>>,
+
+    /// The program responsible for generating randomness and holding the random number
+    pub degenrand_program: Program<'info, Degenrand>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RevealDice<'info> {
+    pub fn coin_remove_requester_ctx(
+        &self,
+    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::Cleanup<'info>> {
+        // Close the requester of the dice into the coin, which is closed to the recipient
+        let cpi_accounts = degenrand::cpi::accounts::Cleanup {
+            authority: self.coin.to_account_info(),
+            requester: self.requester.to_account_info(),
+            initiator: self.coin.to_account_info(),
+            vault: self.oracle_vault.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        CpiContext::new(self.degenrand_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_transfer_ctx(
+        &self,
+        authority: AccountInfo<'info>,
+        from: AccountInfo<'info>,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            authority,
+            from,
+            to,
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+
+    pub fn token_close_escrow_ctx<'b, 'c>(
+        &self,
+    ) -> CpiContext<'_, 'b, 'c, 'info, CloseAccount<'info>> {
+        // The initiator paid the rent of the escrow, it goes to the recipient they picked
+        let cpi_accounts = CloseAccount {
+            account: self.escrow.to_account_info(),
+            destination: self.recipient.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
+}
+
+#[derive(Accounts)]
+pub struct CloseCoin<'info> {
+    pub initiator: Signer<'info>,
+
+    /// Receipt left by the reveal of the coin
+    #[account(
+        mut,
+        seeds = [RECEIPT_PREFIX.as_bytes(), initiator.key().as_ref(), &receipt.nonce.to_le_bytes()],
+        bump = receipt.bump,
+        has_one = initiator @ DegenErrorCode::Unauthorized,
+        close = recipient,
+    )]
+    pub receipt: Box<Account<'info, FlipReceipt>>,
+
+    /// CHECK: Any account chosen by the initiator to receive the rent
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump,
+        space = 8 + size_of::<GameConfig>() + MAX_FEE_RECIPIENTS * size_of::<FeeRecipient>(),
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ DegenErrorCode::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        constraint = config.pending_admin == pending_admin.key() @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    pub pending_admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetFeeRecipient<'info> {
+    #[account(
+        mut,
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    /// Token account collecting the fees of its mint
+    pub fee_recipient: Account<'info, TokenAccount>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitPrizeVault<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [PRIZE_VAULT_PREFIX.as_bytes(), prize_mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<PrizeVault>(),
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [PRIZE_TOKEN_PREFIX.as_bytes(), prize_mint.key().as_ref()],
+        bump,
+        token::mint = prize_mint,
+        token::authority = prize_vault,
+    )]
+    pub prize_token: Account<'info, TokenAccount>,
+
+    /// Token B mint
+    pub prize_mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPrizeReward<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [PRIZE_VAULT_PREFIX.as_bytes(), prize_vault.prize_mint.as_ref()],
+        bump = prize_vault.bump,
+    )]
+    pub prize_vault: Account<'info, PrizeVault>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitTierTable<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TIERS_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<TierTable>() + MAX_TIERS * size_of::<Tier>(),
+    )]
+    pub tier_table: Account<'info, TierTable>,
+
+    /// Token A mint the tiers apply to
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetTiers<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [TIERS_PREFIX.as_bytes(), tier_table.mint.as_ref()],
+        bump = tier_table.bump,
+    )]
+    pub tier_table: Account<'info, TierTable>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitMintConfig<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [MINT_CONFIG_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<MintConfig>(),
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    /// Odds the mint is played at, set up before the mint is allowed
+    #[account(
+        seeds = [TIERS_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump = tier_table.bump,
+    )]
+    pub tier_table: Account<'info, TierTable>,
+
+    /// Token A mint being allowed
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintConfig<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        mut,
+        seeds = [MINT_CONFIG_PREFIX.as_bytes(), mint_config.mint.as_ref()],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub admin: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitTreasury<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TREASURY_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = 8 + size_of::<Treasury>(),
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TREASURY_TOKEN_PREFIX.as_bytes(), mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = treasury,
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
+
+    /// Token A mint the bankroll is held in, the SOL bankroll is opened by init_sol_treasury
+    #[account(
+        constraint = mint.key() != spl_token::native_mint::id() @ DegenErrorCode::NativeMint,
+    )]
+    pub mint: Account<'info, Mint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitSolTreasury<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [TREASURY_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump,
+        space = 8 + size_of::<Treasury>(),
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
-        **ctx
-            .accounts
-            .vault
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() = 0;
+    pub system_program: Program<'info, System>,
+}
 
-        **coin_acc
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() = 0;
+#[derive(Accounts)]
+pub struct DepositSolBankroll<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
 
-        return Ok(());
-    }
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
 
-    pub fn remove_pdas(ctx: Context<Cleanup>) -> Result<()> {
-        let winner = &mut ctx.accounts.winner;
-        if winner.winner != ctx.accounts.initiator.key() {
-            return Err(DegenErrorCode::Unauthorized.into());
-        }
+    #[account(mut)]
+    pub admin: Signer<'info>,
 
-        let (winner_pda, _) = winner_pda(&ctx.accounts.initiator.key());
+    pub system_program: Program<'info, System>,
+}
 
-        if winner_pda != ctx.accounts.winner.key() {
-            return Err(DegenErrorCode::Unauthorized.into());
-        }
+#[derive(Accounts)]
+pub struct WithdrawSolBankroll<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
 
-        **ctx
-            .accounts
-            .initiator
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() += ctx.accounts.winner.to_account_info().lamports();
+    #[account(
+        mut,
+        seeds = [TREASURY_PREFIX.as_bytes(), spl_token::native_mint::id().as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
 
-        **ctx
-            .accounts
-            .winner
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() = 0;
+    /// CHECK: Any account receiving the withdrawn lamports
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
 
-        Ok(())
-    }
+    pub admin: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CreateCoin<'info> {
+pub struct InitJackpot<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
     #[account(
         init,
-        payer = initiator,
-        seeds = [COIN_PREFIX.as_bytes(), initiator.key().as_ref()],
+        payer = admin,
+        seeds = [JACKPOT_PREFIX.as_bytes(), mint.key().as_ref()],
         bump,
-        space = 8 + size_of::<Coin>(),
+        space = 8 + size_of::<Jackpot>(),
     )]
-    pub coin: AccountLoader<'info, Coin>,
+    pub jackpot: Account<'info, Jackpot>,
 
     #[account(
         init,
-        seeds = [VAULT_PREFIX.as_bytes(), mint.key().as_ref(), initiator.key().as_ref(), crate::id().as_ref(),],
+        payer = admin,
+        seeds = [JACKPOT_TOKEN_PREFIX.as_bytes(), mint.key().as_ref()],
         bump,
-        payer = initiator,
-        space = 8 + size_of::<Vault>()
+        token::mint = mint,
+        token::authority = jackpot,
     )]
-    pub vault: Account<'info, Vault>,
-
-    /// CHECK: PDA for calling the Oracle for random number
-    #[account(mut)]
-    pub requester: AccountInfo<'info>,
-
-    /// CHECK: Initiator of the flip
-    #[account(mut)]
-    pub initiator: Signer<'info>,
-
-    /// CHECK: Initiator Token ATA
-    #[account(mut)]
-    pub initiator_ata: Account<'info, TokenAccount>,
-
-    /// CHECK: Account making the random request
-    #[account(mut)]
-    pub oracle: AccountInfo<'info>,
+    pub jackpot_token: Account<'info, TokenAccount>,
 
-    /// CHECK: Token A mint
-    #[account(mut)]
+    /// Token A mint the pool is held in
     pub mint: Account<'info, Mint>,
 
-    /// CHECK: PDA holding the coin toss info.
     #[account(mut)]
-    pub oracle_vault: AccountInfo<'info>,
+    pub admin: Signer<'info>,
 
-    /// CHECK: The program responsible for generating randomness and holding the random number.
-    pub degenrand_program: AccountInfo<'info>,
-
-    /// CHECK: System Variable for getting rent to create a PDA.
     pub rent: Sysvar<'info, Rent>,
 
     pub token_program: Program<'info, Token>,
@@ -329,136 +3822,105 @@ pub struct CreateCoin<'info> {
     pub system_program: Program<'info, System>,
 }
 
-impl<'info> CreateCoin<'info> {
-    pub fn coin_transfer_authority_ctx(
-        &self,
-    ) -> CpiContext<'_, '_, '_, 'info, degenrand::cpi::accounts::TransferAuthority<'info>> {
-        // Transfer authority for the oracle requester to the Coin PDA
-        let cpi_accounts = degenrand::cpi::accounts::TransferAuthority {
-            requester: self.requester.to_account_info(),
-            authority: self.initiator.to_account_info(),
-            new_authority: self.coin.to_account_info(),
-            system_program: self.system_program.to_account_info(),
-        };
-
-        CpiContext::new(self.degenrand_program.clone(), cpi_accounts)
-    }
+#[derive(Accounts)]
+pub struct SetJackpot<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
 
-    pub fn token_approve_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Approve<'info>> {
-        let cpi_accounts = Approve {
-            delegate: self.vault.to_account_info(),
-            authority: self.initiator.to_account_info(),
-            to: self.initiator_ata.to_account_info(),
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
-    }
+    #[account(
+        mut,
+        seeds = [JACKPOT_PREFIX.as_bytes(), jackpot.mint.as_ref()],
+        bump = jackpot.bump,
+    )]
+    pub jackpot: Account<'info, Jackpot>,
 
-    pub fn token_set_authority_ctx<'b, 'c>(
-        &self,
-    ) -> CpiContext<'_, 'b, 'c, 'info, SetAuthority<'info>> {
-        let cpi_accounts = SetAuthority {
-            current_authority: self.initiator.to_account_info(),
-            account_or_mint: self.initiator_ata.to_account_info(),
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
-    }
+    pub admin: Signer<'info>,
 }
 
-//     pub fn token_approve_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Approve<'info>> {
-//         let cpi_accounts = Approve {
-//             delegate: self.vault.to_account_info(),
-//             authority: self.acceptor.to_account_info(),
-//             to: self.acceptor_ata.to_account_info(),
-//         };
-//         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
-//     }
-// }
 #[derive(Accounts)]
-pub struct RevealCoin<'info> {
+pub struct DepositBankroll<'info> {
     #[account(
-        init,
-        payer = authority,
-        seeds = [WINNER_PREFIX.as_bytes(), authority.key().as_ref()],
-        bump,
-        space = 8 + size_of::<Winner>(),
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
     )]
-    /// CHECK: winner
-    pub winner: Account<'info, Winner>,
-
-    /// CHECK: The account flipping
-    #[account(mut, signer)]
-    pub authority: AccountInfo<'info>,
-
-    /// CHECK: The account creating the flip
-    #[account(mut)]
-    pub initiator: AccountInfo<'info>,
-
-    /// CHECK: Initiator Token A ATA
-    #[account(mut)]
-    pub initiator_ata: Box<Account<'info, TokenAccount>>,
-
-    /// CHECK: Admin Token ATA to receive tokens.
-    #[account(mut)]
-    admin_ata: Box<Account<'info, TokenAccount>>,
+    pub config: Account<'info, GameConfig>,
 
-    /// CHECK: Token A mint
-    #[account(mut)]
-    pub mint: Box<Account<'info, Mint>>,
+    #[account(
+        seeds = [TREASURY_PREFIX.as_bytes(), treasury.mint.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
 
-    /// CHECK: PDA storing which is the authority for both ATAs
-    #[account(mut)]
-    pub vault: Box<Account<'info, Vault>>,
+    #[account(
+        mut,
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
 
-    /// CHECK: PDA for calling the Oracle for random number
+    /// Token A account of the admin funding the bankroll
     #[account(mut)]
-    pub requester: AccountInfo<'info>,
+    pub source: Account<'info, TokenAccount>,
 
-    /// CHECK: degenrand program
-    pub degenrand_program: AccountInfo<'info>,
+    pub admin: Signer<'info>,
 
-    // pub rent: Sysvar<'info, Rent>,
     pub token_program: Program<'info, Token>,
-
-    pub system_program: Program<'info, System>,
 }
 
-impl<'info> RevealCoin<'info> {
-    pub fn token_transfer_ctx(
-        &self,
-        authority: AccountInfo<'info>,
-        from: AccountInfo<'info>,
-        to: AccountInfo<'info>,
-    ) -> CpiContext<'_, '_, '_, 'info, Transfer<'info>> {
+impl<'info> DepositBankroll<'info> {
+    pub fn token_deposit_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
         let cpi_accounts = Transfer {
-            authority: authority.to_account_info(),
-            from,
-            to,
-        };
-        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
-    }
-
-    pub fn token_reset_initiator_authority_ctx<'b, 'c>(
-        &self,
-    ) -> CpiContext<'_, 'b, 'c, 'info, SetAuthority<'info>> {
-        let cpi_accounts = SetAuthority {
-            current_authority: self.vault.to_account_info(),
-            account_or_mint: self.initiator_ata.to_account_info(),
+            authority: self.admin.to_account_info(),
+            from: self.source.to_account_info(),
+            to: self.treasury_token.to_account_info(),
         };
         CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
     }
 }
 
 #[derive(Accounts)]
-pub struct Cleanup<'info> {
-    /// CHECK: initiator to return amount to
-    #[account(mut)]
-    pub initiator: Signer<'info>,
+pub struct WithdrawBankroll<'info> {
+    #[account(
+        seeds = [CONFIG_PREFIX.as_bytes()],
+        bump = config.bump,
+        has_one = admin @ DegenErrorCode::Unauthorized,
+    )]
+    pub config: Account<'info, GameConfig>,
+
+    #[account(
+        seeds = [TREASURY_PREFIX.as_bytes(), treasury.mint.as_ref()],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.treasury_token @ DegenErrorCode::InvalidTreasuryAccount,
+    )]
+    pub treasury_token: Account<'info, TokenAccount>,
 
-    /// PDA holding the winner's status
+    /// Token A account receiving the withdrawn bankroll
     #[account(mut)]
-    pub winner: Account<'info, Winner>,
+    pub destination: Account<'info, TokenAccount>,
 
-    pub system_program: Program<'info, System>,
+    pub admin: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawBankroll<'info> {
+    pub fn token_withdraw_ctx<'b, 'c>(&self) -> CpiContext<'_, 'b, 'c, 'info, Transfer<'info>> {
+        let cpi_accounts = Transfer {
+            authority: self.treasury.to_account_info(),
+            from: self.treasury_token.to_account_info(),
+            to: self.destination.to_account_info(),
+        };
+        CpiContext::new(self.token_program.to_account_info(), cpi_accounts)
+    }
 }
 
 // Used for signing CPI to oracle
@@ -466,22 +3928,255 @@ pub struct Cleanup<'info> {
 #[derive(Debug, Default)]
 pub struct Coin {
     pub initiator: Pubkey,
+    pub initiator_ata: Pubkey,
+    pub acceptor: Pubkey,
+    pub acceptor_ata: Pubkey,
     pub is_flipping: bool,
     pub is_cross: bool,
+    pub is_p2p: bool,
+    /// Stake escrowed in the vault lamports, `initiator_ata` is then the initiator
+    pub is_sol: bool,
+    /// The initiator stake stays in their ata with the vault approved as delegate, pulled into
+    /// the escrow once the coin is flipped or accepted
+    pub is_delegated: bool,
+    /// Roll-under target of a dice, 0 for a coin
+    pub dice_target: u8,
+    /// Side picked by the initiator, `HEADS` for SOL coins. Cosmetic for house coins, whose
+    /// outcome comes from the tiers alone
+    pub side: u8,
+    /// Token account sharing the fee, default when the coin has no referrer
+    pub referrer: Pubkey,
     pub created_at: i64,
+    pub nonce: u64,
     pub bump: u8,
 }
 #[derive(Debug, Default, AnchorDeserialize, AnchorSerialize, Clone)]
 pub struct CoinInfo {
     mint_token: Pubkey,
     amount: u64,
+    decimals: u8,
+    prize_mint: Pubkey,
+}
+
+// Used for auditing a flip once it is revealed, never modified afterwards
+#[account]
+#[derive(Debug)]
+pub struct FlipReceipt {
+    pub coin: Pubkey,
+    pub initiator: Pubkey,
+    /// Default for house coins
+    pub acceptor: Pubkey,
+    pub mint: Pubkey,
+    /// Stake in base units
+    pub amount: u64,
+    pub nonce: u64,
+    /// Odds of the initiator in basis points, an even split for P2P coins
+    pub win_bps: u16,
+    /// Roll-under target of a dice, 0 for a coin
+    pub dice_target: u8,
+    /// Random value published by the Oracle
+    pub random: [u8; 64],
+    pub initiator_won: bool,
+    /// Side picked by the initiator, unused for dice
+    pub side: u8,
+    /// Side the coin landed on, unused for dice
+    pub landed: u8,
+    /// Token A paid by the treasury on a house win
+    pub payout: u64,
+    /// Protocol fee charged on the stake
+    pub fee: u64,
+    /// Part of the fee paid to the referrer
+    pub referral_fee: u64,
+    /// Slice of the stake added to the jackpot
+    pub jackpot_contribution: u64,
+    /// Pool paid when the jackpot was hit, to the initiator of a house coin or the winner of a P2P coin
+    pub jackpot_won: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub bump: u8,
+}
+
+// Used for tallying the flips of a player in a mint, updated on every reveal
+#[account]
+#[derive(Debug, Default)]
+pub struct PlayerStats {
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub flips: u64,
+    pub wins: u64,
+    pub losses: u64,
+    /// Sum of the stakes in base units
+    pub wagered: u64,
+    /// Sum of the winnings in base units, the stakes kept on a win are not counted
+    pub won: u64,
+    /// Wins in a row up to the last flip
+    pub current_streak: u64,
+    pub best_streak: u64,
+    pub last_played: i64,
+    pub bump: u8,
+}
+
+impl PlayerStats {
+    pub fn record_flip(&mut self, amount: u64, won: bool, winnings: u64, timestamp: i64) {
+        self.flips = self.flips.saturating_add(1);
+        self.wagered = self.wagered.saturating_add(amount);
+        self.last_played = timestamp;
+
+        // Winnings also count a jackpot hit on a lost flip
+        self.won = self.won.saturating_add(winnings);
+
+        if won {
+            self.wins = self.wins.saturating_add(1);
+            self.current_streak = self.current_streak.saturating_add(1);
+            self.best_streak = self.best_streak.max(self.current_streak);
+        } else {
+            self.losses = self.losses.saturating_add(1);
+            self.current_streak = 0;
+        }
+    }
+}
+
+// Used for holding the admin and settings of the game
+#[account]
+#[derive(Debug, Default)]
+pub struct GameConfig {
+    pub admin: Pubkey,
+    /// Admin proposed by the current one, default when no rotation is underway
+    pub pending_admin: Pubkey,
+    /// At most `MAX_FEE_RECIPIENTS` entries, one per mint
+    pub fee_recipients: Vec<FeeRecipient>,
+    /// Seconds after which an unanswered oracle request can be refunded
+    pub refund_timeout: i64,
+    /// Fee charged on every settled stake in basis points
+    pub fee_bps: u16,
+    /// Share of the fee paid to the referrer of a coin in basis points
+    pub referral_bps: u16,
+    /// No coin is created, flipped or accepted while set
+    pub paused: bool,
+    /// Lamports paid out of the rent of a coin to a keeper revealing it for the initiator
+    pub keeper_reward: u64,
+    /// Oracle every new coin requests its randomness from
+    pub oracle: Pubkey,
+    pub bump: u8,
+}
+
+impl GameConfig {
+    /// Token account collecting the fees of `mint`, if the admin registered one
+    pub fn fee_recipient(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.fee_recipients
+            .iter()
+            .find(|recipient| recipient.mint == *mint)
+            .map(|recipient| recipient.token_account)
+    }
+}
+
+#[derive(Debug, Default, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct FeeRecipient {
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+}
+
+// Used for signing Token B payouts out of the prize token account
+#[account]
+#[derive(Debug, Default)]
+pub struct PrizeVault {
+    pub prize_mint: Pubkey,
+    pub prize_token: Pubkey,
+    pub reward: u64,
+    pub bump: u8,
+}
+
+// Used for looking up the odds of a house coin by the amount staked
+#[account]
+#[derive(Debug, Default)]
+pub struct TierTable {
+    pub mint: Pubkey,
+    /// Ordered by ascending `min_amount`, at most `MAX_TIERS` entries
+    pub tiers: Vec<Tier>,
+    pub bump: u8,
+}
+
+// Used for allowing a mint to be staked, checked when a coin is created
+#[account]
+#[derive(Debug, Default)]
+pub struct MintConfig {
+    pub mint: Pubkey,
+    pub enabled: bool,
+    /// Smallest stake in base units
+    pub min_bet: u64,
+    /// Largest stake in base units
+    pub max_bet: u64,
+    /// Odds the coins of the mint are created against
+    pub tier_table: Pubkey,
+    /// Token B the coins of the mint are played for, unused by SOL coins
+    pub prize_mint: Pubkey,
+    pub bump: u8,
+}
+
+impl MintConfig {
+    pub fn check_bet(&self, amount: u64) -> Result<()> {
+        if amount < self.min_bet || amount > self.max_bet {
+            return Err(DegenErrorCode::BetOutOfRange.into());
+        }
+
+        Ok(())
+    }
 }
 
+// Used for signing transfers out of the house bankroll of a mint
 #[account]
 #[derive(Debug, Default)]
-pub struct Winner {
-    winner: Pubkey,
-    status: bool,
+pub struct Treasury {
+    pub mint: Pubkey,
+    /// Default for the SOL treasury, which holds the bankroll in its own lamports
+    pub treasury_token: Pubkey,
+    /// Payouts reserved for the house coins awaiting their reveal
+    pub liability: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    /// Part of `bankroll` not reserved for a pending coin.
+    pub fn available(&self, bankroll: u64) -> u64 {
+        bankroll.saturating_sub(self.liability)
+    }
+
+    /// Reserves the payout of a new coin, which the bankroll must cover on top of the payouts
+    /// already reserved.
+    pub fn reserve(&mut self, bankroll: u64, payout: u64) -> Result<()> {
+        if self.available(bankroll) < payout {
+            return Err(DegenErrorCode::InsufficientBankroll.into());
+        }
+
+        self.liability += payout;
+
+        Ok(())
+    }
+
+    pub fn release(&mut self, payout: u64) {
+        self.liability = self.liability.saturating_sub(payout);
+    }
+}
+
+// Used for signing the payout of the jackpot pool of a mint
+#[account]
+#[derive(Debug, Default)]
+pub struct Jackpot {
+    pub mint: Pubkey,
+    pub jackpot_token: Pubkey,
+    /// Slice of every stake added to the pool in basis points
+    pub contribution_bps: u16,
+    /// Chance of a flip hitting the jackpot in parts per million
+    pub odds_ppm: u32,
+    pub bump: u8,
+}
+
+#[derive(Debug, Default, AnchorDeserialize, AnchorSerialize, Clone, Copy)]
+pub struct Tier {
+    /// Smallest stake the tier applies to, in hundredths of a token (`TIER_DECIMALS`)
+    pub min_amount: u64,
+    /// Chance of winning Token B in basis points
+    pub win_bps: u16,
 }
 
 // Used for holding the sol balance and transfering to winner
@@ -489,9 +4184,56 @@ pub struct Winner {
 #[derive(Debug, Default)]
 pub struct Vault {
     pub coin_info: CoinInfo,
+    /// Odds of the initiator in basis points, locked when the coin is created. Unused by P2P coins.
+    pub win_bps: u16,
+    /// Treasury payout of a win, reserved until the coin settles. 0 for P2P coins.
+    pub payout: u64,
+    /// Position of the coin among the coins of its initiator
+    pub nonce: u64,
+    pub bump: u8,
+}
+
+// Used for numbering the coins of an initiator
+#[account]
+#[derive(Debug, Default)]
+pub struct CoinCounter {
+    /// Nonce of the next coin
+    pub count: u64,
     pub bump: u8,
 }
 
+#[event]
+pub struct CoinCreated {
+    pub coin: Pubkey,
+    pub initiator: Pubkey,
+    /// Native mint for SOL coins
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub is_p2p: bool,
+    pub is_sol: bool,
+    pub is_delegated: bool,
+    /// Default when the coin has no referrer
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct CoinRevealed {
+    pub coin: Pubkey,
+    pub initiator: Pubkey,
+    /// Default for house coins
+    pub acceptor: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub initiator_won: bool,
+    /// Odds of the tier the coin was played at, an even split for P2P coins
+    pub win_bps: u16,
+    /// Winnings of the winning side, not counting a jackpot hit
+    pub payout: u64,
+    /// Hash of the random value published by the Oracle
+    pub random_digest: [u8; 32],
+}
+
 #[error_code]
 pub enum DegenErrorCode {
     #[msg("You are not authorized to complete this transaction")]
@@ -506,9 +4248,105 @@ pub enum DegenErrorCode {
     #[msg("The Oracle has not provided a response yet")]
     OracleNotCompleted,
 
-    #[msg("Admin Token Pubkey Invalid")]
-    InvalidAdminPubkey,
-
     #[msg("Failed to understand Instruction")]
     FallBacked,
+
+    #[msg("Prize account does not match the prize vault")]
+    InvalidPrizeAccount,
+
+    #[msg("The token account does not hold enough tokens for the stake")]
+    InsufficientFunds,
+
+    #[msg("The coin is not open to an acceptor")]
+    NotP2p,
+
+    #[msg("The coin has not been flipped yet")]
+    CoinNotFlipping,
+
+    #[msg("Tiers must be ordered by minimum amount with odds of at most 10000 basis points")]
+    InvalidTiers,
+
+    #[msg("The decimals do not match the mint")]
+    InvalidDecimals,
+
+    #[msg("The amount overflows once scaled to the mint decimals")]
+    AmountOverflow,
+
+    #[msg("Treasury account does not match the treasury of the mint")]
+    InvalidTreasuryAccount,
+
+    #[msg("Every fee recipient slot is taken")]
+    TooManyFeeRecipients,
+
+    #[msg("Fees must be at most 10000 basis points")]
+    InvalidFees,
+
+    #[msg("Fee recipient is not registered for the mint")]
+    InvalidFeeRecipient,
+
+    #[msg("Referrer account does not match the coin")]
+    InvalidReferrer,
+
+    #[msg("Jackpot contribution must be at most 10000 basis points and odds at most 1000000 parts per million")]
+    InvalidJackpot,
+
+    #[msg("Jackpot account does not match the jackpot of the mint")]
+    InvalidJackpotAccount,
+
+    #[msg("The mint is not enabled for betting")]
+    MintDisabled,
+
+    #[msg("The amount is outside the bet limits of the mint")]
+    BetOutOfRange,
+
+    #[msg("The minimum bet must not exceed the maximum bet")]
+    InvalidBetLimits,
+
+    #[msg("The game is paused, no coin can be created, flipped or accepted")]
+    Paused,
+
+    #[msg("Dice targets must be within the supported range")]
+    InvalidDiceTarget,
+
+    #[msg("The coin is not a dice")]
+    NotDice,
+
+    #[msg("A coin has only heads and tails")]
+    InvalidSide,
+
+    #[msg("The vault is no longer approved for the delegated stake")]
+    DelegationRevoked,
+
+    #[msg("Randomness accounts do not belong to the coin")]
+    InvalidRequester,
+
+    #[msg("Token account or mint does not match the mint of the coin")]
+    InvalidMint,
+
+    #[msg("The refund timeout must be a positive number of seconds")]
+    InvalidRefundTimeout,
+
+    #[msg("SOL is staked through SOL coins, not as wrapped SOL")]
+    NativeMint,
+
+    #[msg("Tier table does not match the mint config")]
+    InvalidTierTable,
+
+    #[msg("The token account already approves a delegate")]
+    DelegateInUse,
+
+    #[msg("The keeper reward exceeds the maximum")]
+    InvalidKeeperReward,
+
+    #[msg("Only the initiator may send the rent of a coin elsewhere")]
+    InvalidRecipient,
+
+    #[msg("The receipt is still kept as an audit trail")]
+    ReceiptRetained,
+
+    #[msg("The bankroll cannot cover the payout of the coin")]
+    InsufficientBankroll,
+
+    #[msg("Oracle is not the one set by the admin")]
+    InvalidOracle,
 }
@@ -1,83 +1,441 @@
-use std::str::FromStr;
-
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token::{TokenAccount, Transfer};
 
-use crate::{DegenErrorCode, COIN_PREFIX, VAULT_PREFIX, WINNER_PREFIX};
+use crate::{
+    DegenErrorCode, Tier, COIN_PREFIX, CONFIG_PREFIX, COUNTER_PREFIX, DICE_EDGE_BPS,
+    DICE_MAX_TARGET, DICE_MIN_TARGET, DICE_SIDES, ESCROW_PREFIX, HEADS, JACKPOT_OFFSET,
+    JACKPOT_PREFIX, JACKPOT_TOKEN_PREFIX, MAX_BPS, MAX_PPM, MAX_TIERS, MINT_CONFIG_PREFIX,
+    PRIZE_TOKEN_PREFIX, PRIZE_VAULT_PREFIX, RECEIPT_PREFIX, STATS_PREFIX, TAILS, TIERS_PREFIX,
+    TIER_DECIMALS, TREASURY_PREFIX, TREASURY_TOKEN_PREFIX, VAULT_PREFIX,
+};
 
 /// Signer Seeds for Vault
 ///  let signer_seeds = &[
 ///     VAULT_PREFIX.as_bytes(),
 ///     mint.key.as_ref(),
+///     initiator.key.as_ref(),
+///     &nonce.to_le_bytes(),
 ///     program_id.as_ref(),
 ///     &[vault_bump],
 /// ];
-pub fn vault_pda(mint: &Pubkey, initiator: &Pubkey) -> (Pubkey, u8) {
+pub fn vault_pda(mint: &Pubkey, initiator: &Pubkey, nonce: u64) -> (Pubkey, u8) {
     Pubkey::find_program_address(
         &[
             VAULT_PREFIX.as_bytes(),
             mint.as_ref(),
             initiator.as_ref(),
-            &crate::id().as_ref(),
+            &nonce.to_le_bytes(),
+            crate::id().as_ref(),
         ],
         &crate::id(),
     )
 }
 
+/// Signer Seeds for the escrow token account of a vault
+pub fn escrow_pda(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ESCROW_PREFIX.as_bytes(), vault.as_ref()], &crate::id())
+}
+
 /// Handles the decimal value.
-/// Converts to appropriate u64 representation.
-pub fn calculate_amount(mint: &Mint, amount: u64) -> u64 {
-    amount.saturating_mul(10_u64.pow((mint.decimals) as u32))
+/// Converts base units of a mint with `decimals` into the `TIER_DECIMALS` precision tiers
+/// are written in, so the same bet gets the same odds whatever the mint.
+pub fn calculate_amount(amount: u64, decimals: u8) -> Result<u64> {
+    let converted = if decimals >= TIER_DECIMALS {
+        // A scale past u64 leaves nothing of the amount
+        Some(
+            10_u64
+                .checked_pow((decimals - TIER_DECIMALS) as u32)
+                .map_or(0, |scale| amount / scale),
+        )
+    } else {
+        10_u64
+            .checked_pow((TIER_DECIMALS - decimals) as u32)
+            .and_then(|scale| amount.checked_mul(scale))
+    };
+
+    converted.ok_or_else(|| DegenErrorCode::AmountOverflow.into())
 }
 
 /// Signer Seeds for Coin
 ///  let signer_seeds =  &[
 ///    COIN_PREFIX.as_bytes(),
 ///    initiator.key.as_ref(),
+///    &nonce.to_le_bytes(),
 ///    &[coin_bump],
 /// ];
-pub fn coin_pda(initiator: &Pubkey) -> (Pubkey, u8) {
-    Pubkey::find_program_address(&[COIN_PREFIX.as_bytes(), initiator.as_ref()], &crate::id())
+pub fn coin_pda(initiator: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            COIN_PREFIX.as_bytes(),
+            initiator.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Signer Seeds for FlipReceipt
+///  let signer_seeds =  &[
+///    RECEIPT_PREFIX.as_bytes(),
+///    initiator.key.as_ref(),
+///    &nonce.to_le_bytes(),
+///    &[receipt_bump],
+/// ];
+pub fn receipt_pda(initiator: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            RECEIPT_PREFIX.as_bytes(),
+            initiator.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+/// Signer Seeds for PlayerStats
+///  let signer_seeds =  &[
+///    STATS_PREFIX.as_bytes(),
+///    player.key.as_ref(),
+///    mint.key.as_ref(),
+///    &[player_stats_bump],
+/// ];
+pub fn player_stats_pda(player: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[STATS_PREFIX.as_bytes(), player.as_ref(), mint.as_ref()],
+        &crate::id(),
+    )
 }
 
-/// Signer Seeds for Winner
+/// Signer Seeds for CoinCounter
 ///  let signer_seeds =  &[
-///    WINNER_PREFIX.as_bytes(),
+///    COUNTER_PREFIX.as_bytes(),
 ///    initiator.key.as_ref(),
-///    &[winner_bump],
+///    &[counter_bump],
+/// ];
+pub fn counter_pda(initiator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COUNTER_PREFIX.as_bytes(), initiator.as_ref()],
+        &crate::id(),
+    )
+}
+/// Signer Seeds for GameConfig
+///  let signer_seeds =  &[
+///    CONFIG_PREFIX.as_bytes(),
+///    &[config_bump],
+/// ];
+pub fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_PREFIX.as_bytes()], &crate::id())
+}
+
+/// Signer Seeds for PrizeVault
+///  let signer_seeds =  &[
+///    PRIZE_VAULT_PREFIX.as_bytes(),
+///    prize_mint.key.as_ref(),
+///    &[prize_vault_bump],
+/// ];
+pub fn prize_vault_pda(prize_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PRIZE_VAULT_PREFIX.as_bytes(), prize_mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Token account escrowing Token B, owned by the PrizeVault
+pub fn prize_token_pda(prize_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PRIZE_TOKEN_PREFIX.as_bytes(), prize_mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Signer Seeds for TierTable
+///  let signer_seeds =  &[
+///    TIERS_PREFIX.as_bytes(),
+///    mint.key.as_ref(),
+///    &[tier_table_bump],
+/// ];
+pub fn tier_table_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TIERS_PREFIX.as_bytes(), mint.as_ref()], &crate::id())
+}
+
+pub fn mint_config_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[MINT_CONFIG_PREFIX.as_bytes(), mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Signer Seeds for Jackpot
+///  let signer_seeds =  &[
+///    JACKPOT_PREFIX.as_bytes(),
+///    mint.key.as_ref(),
+///    &[jackpot_bump],
 /// ];
-pub fn winner_pda(initiator: &Pubkey) -> (Pubkey, u8) {
+pub fn jackpot_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[JACKPOT_PREFIX.as_bytes(), mint.as_ref()], &crate::id())
+}
+
+/// Token account escrowing the jackpot pool of a mint, owned by the Jackpot
+pub fn jackpot_token_pda(mint: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
-        &[WINNER_PREFIX.as_bytes(), initiator.as_ref()],
+        &[JACKPOT_TOKEN_PREFIX.as_bytes(), mint.as_ref()],
         &crate::id(),
     )
 }
-// Bet token 52.5 A 75% chance win Token B
-// Bet token 35 A 50% chance win Token B
-// Bet 17.5 token A 25% chance win Token B
-// Bet 7 token A 10% chance win Token B
-pub fn calculate_probability(amount: u64, random: &[u8]) -> u8 {
-    let r50 = rand50(random[0]);
-    let r75 = rand50(random[1]) | rand50(random[2]);
-    let r90 = rand50(random[3]) |
-     r75;
 
-    match amount {
-        amount if amount >= 5250 => r75,
-        amount if amount >= 3500 && amount < 5250 => r50,
-        amount if amount >= 1750 && amount < 3500 => 1 - r75,
-        amount if amount >= 700 && amount < 1750 => 1 - r90,
-        _ => random[4],
+/// Signer Seeds for Treasury
+///  let signer_seeds =  &[
+///    TREASURY_PREFIX.as_bytes(),
+///    mint.key.as_ref(),
+///    &[treasury_bump],
+/// ];
+pub fn treasury_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[TREASURY_PREFIX.as_bytes(), mint.as_ref()], &crate::id())
+}
+
+/// Token account escrowing the bankroll of a mint, owned by the Treasury
+pub fn treasury_token_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[TREASURY_TOKEN_PREFIX.as_bytes(), mint.as_ref()],
+        &crate::id(),
+    )
+}
+
+/// Tiers must be strictly ascending by minimum amount and never beat certainty.
+pub fn validate_tiers(tiers: &[Tier]) -> Result<()> {
+    if tiers.len() > MAX_TIERS
+        || tiers.iter().any(|tier| tier.win_bps > MAX_BPS)
+        || tiers
+            .windows(2)
+            .any(|pair| pair[0].min_amount >= pair[1].min_amount)
+    {
+        return Err(DegenErrorCode::InvalidTiers.into());
+    }
+
+    Ok(())
+}
+
+/// Odds in basis points of the highest tier the amount reaches.
+/// Amounts below every tier never win.
+pub fn tier_probability(tiers: &[Tier], amount: u64) -> u16 {
+    tiers
+        .iter()
+        .rev()
+        .find(|tier| amount >= tier.min_amount)
+        .map_or(0, |tier| tier.win_bps)
+}
+
+/// Draws a uniform roll in `0..range` from the oracle's random bytes.
+///
+/// Every 8 byte chunk is read as a u64 and split into `range` equal buckets. Values past the
+/// last whole bucket would skew the odds, so they are rejected and the next chunk is used.
+/// Should every chunk be rejected, which is practically impossible, the highest roll is returned.
+pub fn roll(random: &[u8], range: u64) -> u64 {
+    let bucket = u64::MAX / range;
+
+    for chunk in random.chunks_exact(8) {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(chunk);
+
+        let roll = u64::from_le_bytes(bytes) / bucket;
+
+        if roll < range {
+            return roll;
+        }
+    }
+
+    range - 1
+}
+
+/// Draws a uniform roll in `0..MAX_BPS`, see `roll`.
+pub fn roll_bps(random: &[u8]) -> u16 {
+    roll(random, MAX_BPS as u64) as u16
+}
+
+/// Whether the jackpot is hit at `odds_ppm` parts per million.
+/// Reads its own region of the random bytes so it is independent of the flip.
+pub fn jackpot_hit(random: &[u8], odds_ppm: u32) -> bool {
+    roll(&random[JACKPOT_OFFSET..], MAX_PPM as u64) < odds_ppm as u64
+}
+
+/// Whether a flip at `win_bps` odds is won.
+/// Only the bytes before `JACKPOT_OFFSET` decide the flip.
+pub fn flip_won(win_bps: u16, random: &[u8]) -> bool {
+    let random = &random[..JACKPOT_OFFSET.min(random.len())];
+
+    roll_bps(random) < win_bps
+}
+
+pub fn calculate_probability(tiers: &[Tier], amount: u64, random: &[u8]) -> u8 {
+    flip_won(tier_probability(tiers, amount), random) as u8
+}
+
+/// Winnings of a stake at `win_bps` odds, so that a house coin is an even game before fees.
+pub fn calculate_payout(amount: u64, win_bps: u16) -> u64 {
+    if win_bps == 0 {
+        return 0;
+    }
+
+    let payout = amount as u128 * (MAX_BPS - win_bps.min(MAX_BPS)) as u128 / win_bps as u128;
+
+    payout.min(u64::MAX as u128) as u64
+}
+
+pub fn validate_dice_target(target: u8) -> Result<()> {
+    if !(DICE_MIN_TARGET..=DICE_MAX_TARGET).contains(&target) {
+        return Err(DegenErrorCode::InvalidDiceTarget.into());
+    }
+
+    Ok(())
+}
+
+/// Draws the dice roll in `0..DICE_SIDES` from the bytes deciding the flip.
+pub fn roll_dice(random: &[u8]) -> u8 {
+    roll(
+        &random[..JACKPOT_OFFSET.min(random.len())],
+        DICE_SIDES as u64,
+    ) as u8
+}
+
+/// Odds in basis points of a roll landing under `target`.
+pub fn dice_probability(target: u8) -> u16 {
+    (target.min(DICE_SIDES) as u16) * (MAX_BPS / DICE_SIDES as u16)
+}
+
+/// Winnings of a dice won under `target`, the fair return of its odds less `DICE_EDGE_BPS`.
+pub fn calculate_dice_payout(amount: u64, target: u8) -> u64 {
+    let win_bps = dice_probability(target);
+
+    if win_bps == 0 {
+        return 0;
+    }
+
+    let returned = amount as u128 * (MAX_BPS - DICE_EDGE_BPS) as u128 / win_bps as u128;
+
+    returned
+        .saturating_sub(amount as u128)
+        .min(u64::MAX as u128) as u64
+}
+
+/// Lamports of the SOL treasury above its rent exempt minimum.
+pub fn sol_bankroll(treasury: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?.minimum_balance(treasury.data_len());
+
+    Ok(treasury.lamports().saturating_sub(rent))
+}
+
+/// Moves up to `reward` lamports out of the coin to the keeper settling it.
+pub fn pay_keeper(coin: &AccountInfo, keeper: &AccountInfo, reward: u64) -> Result<()> {
+    let reward = reward.min(coin.lamports());
+
+    **coin.try_borrow_mut_lamports()? -= reward;
+    **keeper.try_borrow_mut_lamports()? += reward;
+
+    Ok(())
+}
+
+pub fn validate_bet_limits(min_bet: u64, max_bet: u64) -> Result<()> {
+    if min_bet > max_bet {
+        return Err(DegenErrorCode::InvalidBetLimits.into());
     }
+
+    Ok(())
 }
+
+/// Slice of a stake going to the jackpot pool.
+pub fn calculate_contribution(amount: u64, contribution_bps: u16) -> u64 {
+    (amount as u128 * contribution_bps.min(MAX_BPS) as u128 / MAX_BPS as u128) as u64
+}
+
+pub fn validate_jackpot(contribution_bps: u16, odds_ppm: u32) -> Result<()> {
+    if contribution_bps > MAX_BPS || odds_ppm > MAX_PPM {
+        return Err(DegenErrorCode::InvalidJackpot.into());
+    }
+
+    Ok(())
+}
+
+/// Splits the fee charged on a stake into the protocol part and the referrer's part.
+pub fn calculate_fees(amount: u64, fee_bps: u16, referral_bps: u16, referred: bool) -> (u64, u64) {
+    let fee = (amount as u128 * fee_bps.min(MAX_BPS) as u128 / MAX_BPS as u128) as u64;
+
+    let referral_fee = if referred {
+        (fee as u128 * referral_bps.min(MAX_BPS) as u128 / MAX_BPS as u128) as u64
+    } else {
+        0
+    };
+
+    (fee - referral_fee, referral_fee)
+}
+
 pub fn rand50(rand: u8) -> u8 {
     &rand & 1
 }
 
-const ADMIN_TOKEN_A_PUBKEY: &str = "9kjgGV2PjKgpu4r7wFqWRhV6jq1SjJGgQjZYh6bG5Asa";
+pub fn validate_side(side: u8) -> Result<()> {
+    if side != HEADS && side != TAILS {
+        return Err(DegenErrorCode::InvalidSide.into());
+    }
+
+    Ok(())
+}
+
+/// Side a fair toss lands on.
+pub fn toss(random: &[u8]) -> u8 {
+    rand50(random[0])
+}
+
+/// Side a house coin lands on, the one of the initiator exactly when the tiers let them win.
+/// The tiers alone decide the outcome, the side picked for a house coin does not change the odds.
+pub fn landed_side(side: u8, initiator_won: bool) -> u8 {
+    if initiator_won {
+        side
+    } else {
+        side ^ 1
+    }
+}
+
+/// A delegated stake can still be pulled by `delegate`: the approval covers `amount` and the
+/// tokens were not moved out since.
+pub fn check_delegation(
+    account: &spl_token::state::Account,
+    delegate: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    if account.delegate != COption::Some(*delegate)
+        || account.delegated_amount < amount
+        || account.amount < amount
+    {
+        return Err(DegenErrorCode::DelegationRevoked.into());
+    }
+
+    Ok(())
+}
+
+/// Pulls a delegated stake out of `from` into the escrow, the vault signing as its delegate.
+pub fn pull_delegated_stake<'info>(
+    token_program: AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    escrow: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    amount: u64,
+    vault_seeds: &[&[u8]],
+) -> Result<()> {
+    let account: Account<TokenAccount> = Account::try_from(from)?;
+
+    check_delegation(&account, vault.key, amount)?;
 
-pub fn admin_account_pubkey() -> Result<Pubkey> {
-    Pubkey::from_str(ADMIN_TOKEN_A_PUBKEY).map_err(|_| DegenErrorCode::InvalidAdminPubkey.into())
+    let cpi_accounts = Transfer {
+        from: from.clone(),
+        to: escrow,
+        authority: vault,
+    };
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(token_program, cpi_accounts, &[vault_seeds]),
+        amount,
+    )
 }
 
 #[cfg(test)]
@@ -85,29 +443,263 @@ mod tests {
     use super::*;
     use rand::Rng;
 
+    const TIERS: [Tier; 4] = [
+        Tier {
+            min_amount: 700,
+            win_bps: 1000,
+        },
+        Tier {
+            min_amount: 1750,
+            win_bps: 2500,
+        },
+        Tier {
+            min_amount: 3500,
+            win_bps: 5000,
+        },
+        Tier {
+            min_amount: 5250,
+            win_bps: 7500,
+        },
+    ];
+
     fn random_bytes() -> Vec<u8> {
         let mut rng = rand::thread_rng();
 
-        (0..64).map(|_| rng.gen_range(0..=1)).collect()
+        (0..64).map(|_| rng.gen()).collect()
+    }
+
+    #[test]
+    fn test_tier_probability() {
+        assert_eq!(tier_probability(&TIERS, 699), 0);
+        assert_eq!(tier_probability(&TIERS, 700), 1000);
+        assert_eq!(tier_probability(&TIERS, 3499), 2500);
+        assert_eq!(tier_probability(&TIERS, 1_000_000), 7500);
+        assert_eq!(tier_probability(&[], 1_000_000), 0);
+    }
+
+    #[test]
+    fn test_validate_tiers() {
+        assert!(validate_tiers(&TIERS).is_ok());
+        assert!(validate_tiers(&[]).is_ok());
+
+        let mut unordered = TIERS;
+        unordered.swap(0, 1);
+        assert!(validate_tiers(&unordered).is_err());
+
+        let mut above_certainty = TIERS;
+        above_certainty[3].win_bps = MAX_BPS + 1;
+        assert!(validate_tiers(&above_certainty).is_err());
+
+        assert!(validate_tiers(&[TIERS[0]; MAX_TIERS + 1]).is_err());
+    }
+
+    #[test]
+    fn test_roll_bps() {
+        assert_eq!(roll_bps(&[0u8; 64]), 0);
+        assert_eq!(roll_bps(&[u8::MAX; 64]), MAX_BPS - 1);
+
+        // A chunk past the last whole bucket is skipped for the next one
+        let mut random = [0u8; 64];
+        random[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        random[8..16].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+        assert_eq!(roll_bps(&random), MAX_BPS / 2);
+    }
+
+    #[test]
+    fn test_roll_bps_is_uniform() {
+        let draws = 100_000;
+        let wins = (0..draws)
+            .filter(|_| roll_bps(&random_bytes()) < 4750)
+            .count();
+
+        // 47.5% odds, well within five standard deviations
+        assert!((46_700..48_300).contains(&wins), "{} wins", wins);
+    }
+
+    #[test]
+    fn test_calculate_amount() {
+        // 52.5 Token A whatever the mint
+        assert_eq!(calculate_amount(5250, 2).unwrap(), 5250);
+        assert_eq!(calculate_amount(52_500_000_000, 9).unwrap(), 5250);
+        assert_eq!(calculate_amount(525, 1).unwrap(), 5250);
+
+        // Dust below a hundredth of a token rounds down
+        assert_eq!(calculate_amount(9_999_999, 9).unwrap(), 0);
+        assert_eq!(calculate_amount(u64::MAX, 30).unwrap(), 0);
+
+        assert!(calculate_amount(u64::MAX, 0).is_err());
+    }
+
+    #[test]
+    fn test_calculate_payout() {
+        // An even toss doubles the stake, a 75% tier pays a third of it
+        assert_eq!(calculate_payout(5250, 5000), 5250);
+        assert_eq!(calculate_payout(5250, 7500), 1750);
+        assert_eq!(calculate_payout(700, 1000), 6300);
+
+        assert_eq!(calculate_payout(5250, MAX_BPS), 0);
+        assert_eq!(calculate_payout(5250, 0), 0);
+        assert_eq!(calculate_payout(u64::MAX, 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_calculate_fees() {
+        // 2% of the stake, a quarter of it to the referrer
+        assert_eq!(calculate_fees(5250, 200, 2500, true), (79, 26));
+        assert_eq!(calculate_fees(5250, 200, 2500, false), (105, 0));
+
+        assert_eq!(calculate_fees(5250, 0, 2500, true), (0, 0));
+        assert_eq!(calculate_fees(5250, MAX_BPS, MAX_BPS, true), (0, 5250));
+        assert_eq!(calculate_fees(u64::MAX, MAX_BPS, 0, false), (u64::MAX, 0));
+    }
+
+    #[test]
+    fn test_jackpot_hit() {
+        // The flip and the jackpot read separate halves of the random bytes
+        let mut random = [0u8; 64];
+        random[JACKPOT_OFFSET..].copy_from_slice(&[u8::MAX; 64 - JACKPOT_OFFSET]);
+
+        assert_eq!(calculate_probability(&TIERS, 5250, &random), 1);
+        assert!(!jackpot_hit(&random, MAX_PPM - 1));
+        assert!(jackpot_hit(&random, MAX_PPM));
+
+        assert!(jackpot_hit(&[0u8; 64], 1));
+        assert!(!jackpot_hit(&[0u8; 64], 0));
+
+        let hits = (0..100_000)
+            .filter(|_| jackpot_hit(&random_bytes(), 100_000))
+            .count();
+
+        // 10% odds, well within five standard deviations
+        assert!((9_500..10_500).contains(&hits), "{} hits", hits);
+    }
+
+    #[test]
+    fn test_check_bet() {
+        let mint_config = crate::MintConfig {
+            min_bet: 100,
+            max_bet: 5250,
+            ..Default::default()
+        };
+
+        assert!(mint_config.check_bet(99).is_err());
+        assert!(mint_config.check_bet(100).is_ok());
+        assert!(mint_config.check_bet(5250).is_ok());
+        assert!(mint_config.check_bet(5251).is_err());
+
+        assert!(validate_bet_limits(5250, 5250).is_ok());
+        assert!(validate_bet_limits(5251, 5250).is_err());
+    }
+
+    #[test]
+    fn test_sides() {
+        assert!(validate_side(HEADS).is_ok());
+        assert!(validate_side(TAILS).is_ok());
+        assert!(validate_side(2).is_err());
+
+        assert_eq!(toss(&[0u8; 64]), HEADS);
+        assert_eq!(toss(&[1u8; 64]), TAILS);
+
+        assert_eq!(landed_side(TAILS, true), TAILS);
+        assert_eq!(landed_side(TAILS, false), HEADS);
+        assert_eq!(landed_side(HEADS, false), TAILS);
+    }
+
+    #[test]
+    fn test_delegation() {
+        let vault = Pubkey::new_unique();
+
+        let account = spl_token::state::Account {
+            amount: 5000,
+            delegate: COption::Some(vault),
+            delegated_amount: 5000,
+            ..Default::default()
+        };
+
+        assert!(check_delegation(&account, &vault, 5000).is_ok());
+        assert!(check_delegation(&account, &Pubkey::new_unique(), 5000).is_err());
+
+        // Revoked, partly spent or drained approvals are all rejected
+        let revoked = spl_token::state::Account {
+            delegate: COption::None,
+            delegated_amount: 0,
+            ..account
+        };
+        assert!(check_delegation(&revoked, &vault, 5000).is_err());
+
+        let spent = spl_token::state::Account {
+            delegated_amount: 4999,
+            ..account
+        };
+        assert!(check_delegation(&spent, &vault, 5000).is_err());
+
+        let drained = spl_token::state::Account {
+            amount: 4999,
+            ..account
+        };
+        assert!(check_delegation(&drained, &vault, 5000).is_err());
+    }
+
+    #[test]
+    fn test_dice() {
+        assert!(validate_dice_target(DICE_MIN_TARGET - 1).is_err());
+        assert!(validate_dice_target(DICE_MIN_TARGET).is_ok());
+        assert!(validate_dice_target(DICE_MAX_TARGET).is_ok());
+        assert!(validate_dice_target(DICE_MAX_TARGET + 1).is_err());
+
+        assert_eq!(roll_dice(&[0u8; 64]), 0);
+        assert_eq!(roll_dice(&[u8::MAX; 64]), DICE_SIDES - 1);
+
+        // Only the bytes of the flip are rolled
+        let mut random = [0u8; 64];
+        random[JACKPOT_OFFSET..].copy_from_slice(&[u8::MAX; 32]);
+        assert_eq!(roll_dice(&random), 0);
+
+        // Even odds return 1.98x, the longest ones 49.5x
+        assert_eq!(dice_probability(50), 5000);
+        assert_eq!(calculate_dice_payout(10_000, 50), 9_800);
+        assert_eq!(calculate_dice_payout(10_000, 2), 485_000);
+        assert_eq!(calculate_dice_payout(10_000, 98), 102);
+    }
+
+    #[test]
+    fn test_record_flip() {
+        let mut stats = crate::PlayerStats::default();
+
+        for (won, timestamp) in [(true, 1), (true, 2), (false, 3), (true, 4)] {
+            stats.record_flip(100, won, if won { 50 } else { 0 }, timestamp);
+        }
+
+        assert_eq!((stats.flips, stats.wins, stats.losses), (4, 3, 1));
+        assert_eq!((stats.wagered, stats.won), (400, 150));
+        assert_eq!((stats.current_streak, stats.best_streak), (1, 2));
+        assert_eq!(stats.last_played, 4);
+    }
+
+    #[test]
+    fn test_probability_extremes() {
+        assert_eq!(calculate_probability(&TIERS, 5250, &[0u8; 64]), 1);
+        assert_eq!(calculate_probability(&TIERS, 5250, &[u8::MAX; 64]), 0);
+        assert_eq!(calculate_probability(&TIERS, 100, &[0u8; 64]), 0);
     }
 
     #[test]
     fn test_probabilities() {
         let rand = random_bytes();
 
-        let v = calculate_probability(5250, rand.as_slice());
+        let v = calculate_probability(&TIERS, 5250, rand.as_slice());
         println!("{}", v);
 
-        let v = calculate_probability(3500, rand.as_slice());
+        let v = calculate_probability(&TIERS, 3500, rand.as_slice());
         println!("{}", v);
 
-        let v = calculate_probability(1750, rand.as_slice());
+        let v = calculate_probability(&TIERS, 1750, rand.as_slice());
         println!("{}", v);
 
-        let v = calculate_probability(1000, rand.as_slice());
+        let v = calculate_probability(&TIERS, 1000, rand.as_slice());
         println!("{}", v);
 
-        let v = calculate_probability(700, rand.as_slice());
+        let v = calculate_probability(&TIERS, 700, rand.as_slice());
         println!("{}", v);
     }
 
@@ -115,7 +707,7 @@ mod tests {
     fn test_probability_5250_amount() {
         let v = (0..=100).fold(0, |mut acc, _| {
             let rand = random_bytes();
-            acc += calculate_probability(5250, rand.as_slice());
+            acc += calculate_probability(&TIERS, 5250, rand.as_slice());
             acc
         });
 
@@ -126,7 +718,7 @@ mod tests {
     fn test_probability_3500_amount() {
         let v = (0..=100).fold(0, |mut acc, _| {
             let rand = random_bytes();
-            acc += calculate_probability(3500, rand.as_slice());
+            acc += calculate_probability(&TIERS, 3500, rand.as_slice());
             acc
         });
 
@@ -137,7 +729,7 @@ mod tests {
     fn test_probability_1750_amount() {
         let v = (0..=100).fold(0, |mut acc: u8, _| {
             let rand = random_bytes();
-            acc += calculate_probability(1750, rand.as_slice());
+            acc += calculate_probability(&TIERS, 1750, rand.as_slice());
             acc
         });
 
@@ -148,7 +740,7 @@ mod tests {
     fn test_probability_700_amount() {
         let v = (0..=100).fold(0, |mut acc: u8, _| {
             let rand = random_bytes();
-            acc += calculate_probability(700, rand.as_slice());
+            acc += calculate_probability(&TIERS, 700, rand.as_slice());
             acc
         });
 
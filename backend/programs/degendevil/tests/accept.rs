@@ -0,0 +1,240 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_sdk::instruction::AccountMeta;
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const PLAYER_FUNDS: u64 = 1000000;
+
+#[tokio::test]
+async fn accept_and_reveal_p2p_coin() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+
+    for account in [&alice_token_x_account, &bob_token_x_account] {
+        mint_token(&token_x.pubkey(), &account.pubkey(), PLAYER_FUNDS, &mut ctx).await?;
+    }
+
+    let House {
+        prize_vault,
+        prize_token,
+        tier_table,
+        treasury,
+        treasury_token,
+        jackpot,
+        jackpot_token,
+        fee_recipient,
+        counter,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let (coin_pda, coin_bump) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (receipt_pda, _) = degendevil::receipt_pda(&alice.pubkey(), 0);
+    let (player_stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+    let (acceptor_stats, _) = degendevil::player_stats_pda(&bob.pubkey(), &token_x.pubkey());
+    let (vault_pda, vault_bump) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+
+    let (requester, _) = degenrand::requestor_pda(&coin_pda);
+    let (oracle_vault, _) = degenrand::vault_pda(&coin_pda);
+
+    let ix = Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::CreateCoin {
+            counter,
+            coin: coin_pda,
+            vault: vault_pda,
+            escrow: degendevil::escrow_pda(&vault_pda).0,
+            initiator: alice.pubkey(),
+            requester,
+            oracle_vault,
+            oracle: oracle.pubkey(),
+            initiator_ata: alice_token_x_account.pubkey(),
+            mint: token_x.pubkey(),
+            config: degendevil::config_pda().0,
+            mint_config: degendevil::mint_config_pda(&token_x.pubkey()).0,
+            tier_table,
+            treasury,
+            treasury_token,
+            jackpot,
+            prize_vault,
+            player_stats,
+            degenrand_program: degenrand::id(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::CreateCoin {
+            amount: AMOUNT,
+            decimals: 2,
+            coin_bump,
+            vault_bump,
+            p2p: true,
+            side: degendevil::TAILS,
+            delegated: false,
+            referrer: None,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // Nothing has been requested from the oracle until bob accepts
+    assert!(publish_random(&oracle, &coin_pda, [0u8; 64], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::AcceptCoin {
+            coin: coin_pda,
+            vault: vault_pda,
+            escrow: degendevil::escrow_pda(&vault_pda).0,
+            requester,
+            initiator: alice.pubkey(),
+            acceptor: bob.pubkey(),
+            acceptor_ata: bob_token_x_account.pubkey(),
+            acceptor_stats,
+            oracle: oracle.pubkey(),
+            oracle_vault,
+            config: degendevil::config_pda().0,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::AcceptCoin {}.data(),
+    };
+
+    process_instruction(ix, &[&bob], &mut ctx).await?;
+
+    // Both stakes sit in the escrow, the atas stay with their owners
+    let (escrow, _) = degendevil::escrow_pda(&vault_pda);
+    assert_eq!(token_balance(&escrow, &mut ctx).await?, 2 * AMOUNT);
+    assert_eq!(
+        token_account(&bob_token_x_account.pubkey(), &mut ctx)
+            .await?
+            .owner,
+        bob.pubkey()
+    );
+
+    // An odd first byte lands tails, the side alice picked, and she wins the pot
+    let mut random = [0u8; 64];
+    random[0] = 1;
+
+    publish_random(&oracle, &coin_pda, random, &mut ctx).await?;
+
+    let mut reveal_coin_accounts = degendevil::accounts::RevealCoin {
+        authority: alice.pubkey(),
+        initiator: alice.pubkey(),
+        recipient: alice.pubkey(),
+        receipt: receipt_pda,
+        player_stats,
+        initiator_ata: alice_token_x_account.pubkey(),
+        mint: token_x.pubkey(),
+        coin: coin_pda,
+        vault: vault_pda,
+        escrow: degendevil::escrow_pda(&vault_pda).0,
+        prize_vault,
+        prize_token,
+        initiator_prize_ata: alice_token_y_account.pubkey(),
+        treasury,
+        treasury_token,
+        jackpot,
+        jackpot_token,
+        config: degendevil::config_pda().0,
+        fee_recipient,
+        requester,
+        oracle_vault,
+        degenrand_program: degenrand::id(),
+        token_program: spl_token::id(),
+        system_program: anchor_lang::solana_program::system_program::id(),
+    }
+    .to_account_metas(None);
+
+    // Leaving out the acceptor accounts fails cleanly instead of panicking
+    let ix = Instruction {
+        program_id: degendevil::id(),
+        accounts: reveal_coin_accounts.clone(),
+        data: degendevil::instruction::RevealCoin {}.data(),
+    };
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::Unauthorized.into())
+    );
+
+    reveal_coin_accounts.push(AccountMeta::new_readonly(bob.pubkey(), false));
+    reveal_coin_accounts.push(AccountMeta::new(bob_token_x_account.pubkey(), false));
+    reveal_coin_accounts.push(AccountMeta::new(acceptor_stats, false));
+
+    let ix = Instruction {
+        program_id: degendevil::id(),
+        accounts: reveal_coin_accounts,
+        data: degendevil::instruction::RevealCoin {}.data(),
+    };
+
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let alice_account = token_account(&alice_token_x_account.pubkey(), &mut ctx).await?;
+    let bob_account = token_account(&bob_token_x_account.pubkey(), &mut ctx).await?;
+
+    assert_eq!(alice_account.amount, PLAYER_FUNDS + AMOUNT);
+    assert_eq!(alice_account.owner, alice.pubkey());
+    assert_eq!(bob_account.amount, PLAYER_FUNDS - AMOUNT);
+    assert_eq!(bob_account.owner, bob.pubkey());
+    // The house takes no part in a P2P coin
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL
+    );
+    assert!(ctx.banks_client.get_account(escrow).await?.is_none());
+
+    let receipt: degendevil::FlipReceipt = get_account(&receipt_pda, &mut ctx).await?;
+    assert!(receipt.initiator_won);
+    assert_eq!(receipt.side, degendevil::TAILS);
+    assert_eq!(receipt.landed, degendevil::TAILS);
+    assert_eq!(receipt.acceptor, bob.pubkey());
+    assert_eq!(receipt.win_bps, 5000);
+
+    // Alice took bob's stake
+    let stats: degendevil::PlayerStats = get_account(&player_stats, &mut ctx).await?;
+    assert_eq!(stats.wins, 1);
+    assert_eq!(stats.won, AMOUNT);
+
+    // Bob's tally records the loss
+    let stats: degendevil::PlayerStats = get_account(&acceptor_stats, &mut ctx).await?;
+    assert_eq!(stats.player, bob.pubkey());
+    assert_eq!(stats.flips, 1);
+    assert_eq!(stats.losses, 1);
+    assert_eq!(stats.wagered, AMOUNT);
+    assert_eq!(stats.won, 0);
+
+    Ok(())
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use {solana_program_test::*, solana_sdk::signature::Signer, utils::*};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+#[tokio::test]
+async fn cancel_before_flip() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let (coin_pda, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (vault_pda, _) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+    let (requester, _) = degenrand::requestor_pda(&coin_pda);
+
+    let balance_before = ctx.banks_client.get_balance(alice.pubkey()).await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // Only the stake moved into the escrow
+    let (escrow, _) = degendevil::escrow_pda(&vault_pda);
+    assert_eq!(token_balance(&escrow, &mut ctx).await?, AMOUNT);
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+
+    let ix = cancel_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The stake is back with alice and the PDAs are closed
+    let alice_account = token_account(&alice_token_x_account.pubkey(), &mut ctx).await?;
+    assert_eq!(alice_account.owner, alice.pubkey());
+    assert_eq!(alice_account.amount, INITIATOR_FUNDS);
+
+    assert!(ctx.banks_client.get_account(coin_pda).await?.is_none());
+    assert!(ctx.banks_client.get_account(vault_pda).await?.is_none());
+    assert!(ctx.banks_client.get_account(escrow).await?.is_none());
+    assert!(ctx.banks_client.get_account(requester).await?.is_none());
+
+    // Fees are paid by ctx.payer so alice gets all of the rent back
+    assert_eq!(
+        ctx.banks_client.get_balance(alice.pubkey()).await?,
+        balance_before
+    );
+
+    // The next coin of alice gets the following nonce
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        1,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_after_flip_fails() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let ix = cancel_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+fn close_coin_ix(initiator: &Pubkey, nonce: u64, recipient: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::CloseCoin {
+            initiator: *initiator,
+            receipt: degendevil::receipt_pda(initiator, nonce).0,
+            recipient: *recipient,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::CloseCoin {}.data(),
+    }
+}
+
+/// `ix` sent by `authority`, closing the accounts of the coin to `recipient`.
+fn reveal_to(mut ix: Instruction, authority: &Pubkey, recipient: &Pubkey) -> Instruction {
+    // The authority comes first, the recipient right after the initiator
+    ix.accounts[0] = AccountMeta::new(*authority, true);
+    ix.accounts[2].pubkey = *recipient;
+
+    ix
+}
+
+#[tokio::test]
+async fn close_completed_coin() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+
+    // A keeper cannot take the rent of the coin
+    let ix_bob = reveal_to(ix.clone(), &bob.pubkey(), &bob.pubkey());
+    assert_eq!(
+        error_code(process_instruction(ix_bob, &[&bob], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InvalidRecipient.into())
+    );
+
+    // Alice sends it wherever she likes
+    let recipient = Keypair::new();
+    let alice_balance = ctx.banks_client.get_balance(alice.pubkey()).await?;
+
+    let ix = reveal_to(ix, &alice.pubkey(), &recipient.pubkey());
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The reveal closes the coin, its vault and the randomness accounts to the recipient
+    let (vault, _) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+    for account in [
+        coin,
+        vault,
+        degendevil::escrow_pda(&vault).0,
+        degenrand::requestor_pda(&coin).0,
+        degenrand::vault_pda(&coin).0,
+    ] {
+        assert!(ctx.banks_client.get_account(account).await?.is_none());
+    }
+
+    let (receipt, _) = degendevil::receipt_pda(&alice.pubkey(), 0);
+    let receipt_rent = ctx.banks_client.get_balance(receipt).await?;
+    assert!(receipt_rent > 0);
+
+    assert!(ctx.banks_client.get_balance(recipient.pubkey()).await? > 0);
+    assert_eq!(
+        ctx.banks_client.get_balance(alice.pubkey()).await?,
+        alice_balance - receipt_rent
+    );
+
+    // Only the receipt is left, kept as an audit trail for a while
+    let ix = close_coin_ix(&alice.pubkey(), 0, &alice.pubkey());
+    assert_eq!(
+        error_code(process_instruction(ix.clone(), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::ReceiptRetained.into())
+    );
+
+    warp_clock(degendevil::RECEIPT_RETENTION, &mut ctx).await?;
+
+    // Then closable by alice alone
+    let mut ix_bob = ix.clone();
+    ix_bob.accounts[0].pubkey = bob.pubkey();
+    assert!(process_instruction(ix_bob, &[&bob], &mut ctx)
+        .await
+        .is_err());
+
+    process_instruction(ix.clone(), &[&alice], &mut ctx).await?;
+
+    assert!(ctx.banks_client.get_account(receipt).await?.is_none());
+    assert_eq!(
+        ctx.banks_client.get_balance(alice.pubkey()).await?,
+        alice_balance
+    );
+
+    // Nothing left to close
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+fn propose_admin_ix(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::ProposeAdmin {
+            new_admin: *new_admin,
+        }
+        .data(),
+    }
+}
+
+fn accept_admin_ix(pending_admin: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::AcceptAdmin {
+            config: degendevil::config_pda().0,
+            pending_admin: *pending_admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::AcceptAdmin {}.data(),
+    }
+}
+
+fn set_refund_timeout_ix(admin: &Pubkey, refund_timeout: i64) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetRefundTimeout { refund_timeout }.data(),
+    }
+}
+
+#[tokio::test]
+async fn only_upgrade_authority_initializes() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let result = initialize_config(&alice, &oracle.pubkey(), &mut ctx)
+        .await
+        .map(|_| ());
+    assert_eq!(
+        error_code(result),
+        Some(degendevil::DegenErrorCode::Unauthorized.into())
+    );
+
+    let config = initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    let game_config: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert_eq!(game_config.admin, winner_mint_holder.pubkey());
+    assert_eq!(game_config.oracle, oracle.pubkey());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rotate_admin() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let config = initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    // Only the admin proposes a successor
+    let ix = propose_admin_ix(&alice.pubkey(), &alice.pubkey());
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = propose_admin_ix(&winner_mint_holder.pubkey(), &alice.pubkey());
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // The proposal alone hands over nothing
+    let ix = set_refund_timeout_ix(&alice.pubkey(), 60);
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // Nobody but the proposed admin can accept
+    let ix = accept_admin_ix(&bob.pubkey());
+    assert!(process_instruction(ix, &[&bob], &mut ctx).await.is_err());
+
+    let ix = accept_admin_ix(&alice.pubkey());
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let game_config: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert_eq!(game_config.admin, alice.pubkey());
+    assert_eq!(game_config.pending_admin, Pubkey::default());
+
+    // The previous admin is locked out, the new one is in charge
+    let ix = set_refund_timeout_ix(&winner_mint_holder.pubkey(), 60);
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = set_refund_timeout_ix(&alice.pubkey(), 60);
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let game_config: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert_eq!(game_config.refund_timeout, 60);
+
+    // A request must get some time to be answered before it is refundable
+    for refund_timeout in [0, -60] {
+        let ix = set_refund_timeout_ix(&alice.pubkey(), refund_timeout);
+        assert_eq!(
+            error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+            Some(degendevil::DegenErrorCode::InvalidRefundTimeout.into())
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn set_fee_recipients() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let first_recipient =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
+    let second_recipient =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
+    let y_recipient =
+        create_token_account(&token_y.pubkey(), &winner_mint_holder, &mut ctx).await?;
+
+    let config = initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    // Only the admin chooses where fees go
+    let ix = set_fee_recipient_ix(&alice.pubkey(), &first_recipient.pubkey());
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    for recipient in [&first_recipient, &y_recipient, &second_recipient] {
+        let ix = set_fee_recipient_ix(&winner_mint_holder.pubkey(), &recipient.pubkey());
+        process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+    }
+
+    // A mint keeps a single recipient, the latest one
+    let game_config: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert_eq!(game_config.fee_recipients.len(), 2);
+    assert_eq!(
+        game_config.fee_recipient(&token_x.pubkey()),
+        Some(second_recipient.pubkey())
+    );
+    assert_eq!(
+        game_config.fee_recipient(&token_y.pubkey()),
+        Some(y_recipient.pubkey())
+    );
+
+    Ok(())
+}
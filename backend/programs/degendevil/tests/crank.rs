@@ -0,0 +1,161 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        signature::Signer,
+    },
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+const KEEPER_REWARD: u64 = 100000;
+
+fn set_keeper_reward_ix(admin: &Pubkey, keeper_reward: u64) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetKeeperReward { keeper_reward }.data(),
+    }
+}
+
+#[tokio::test]
+async fn keeper_reveals_for_initiator() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        config,
+        prize_vault,
+        treasury_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // Only the admin sets the reward
+    let ix = set_keeper_reward_ix(&alice.pubkey(), KEEPER_REWARD);
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // Nor beyond what the rent of a coin pays for
+    let ix = set_keeper_reward_ix(&winner_mint_holder.pubkey(), u64::MAX);
+    assert_eq!(
+        error_code(process_instruction(ix, &[&winner_mint_holder], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InvalidKeeperReward.into())
+    );
+
+    let ix = set_keeper_reward_ix(&winner_mint_holder.pubkey(), KEEPER_REWARD);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let stored: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert_eq!(stored.keeper_reward, KEEPER_REWARD);
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    // Bob cranks the coin of alice, settling it on her behalf
+    let mut ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    ix.accounts[0] = AccountMeta::new(bob.pubkey(), true);
+
+    // Nothing to settle until the oracle has answered
+    assert!(process_instruction(ix.clone(), &[&bob], &mut ctx)
+        .await
+        .is_err());
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+    let alice_balance = ctx.banks_client.get_balance(alice.pubkey()).await?;
+    let bob_balance = ctx.banks_client.get_balance(bob.pubkey()).await?;
+
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    process_instruction(ix, &[&bob], &mut ctx).await?;
+
+    // The outcome is the one of alice, the highest roll loses at every tier
+    let (receipt, _) = degendevil::receipt_pda(&alice.pubkey(), 0);
+    let stored: degendevil::FlipReceipt = get_account(&receipt, &mut ctx).await?;
+    assert_eq!(stored.initiator, alice.pubkey());
+    assert!(!stored.initiator_won);
+
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL + AMOUNT
+    );
+    assert!(ctx.banks_client.get_account(coin).await?.is_none());
+
+    // Bob is paid back the receipt and earns the reward, the rest of the rent is back with alice
+    assert_eq!(
+        ctx.banks_client.get_balance(bob.pubkey()).await?,
+        bob_balance + KEEPER_REWARD
+    );
+    assert!(ctx.banks_client.get_balance(alice.pubkey()).await? > alice_balance);
+
+    Ok(())
+}
@@ -0,0 +1,341 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::solana_program::program_option::COption;
+use {
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::AccountMeta,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+struct DelegatedCoin {
+    ctx: ProgramTestContext,
+    alice: Keypair,
+    oracle: Keypair,
+    token_x: Pubkey,
+    token_y: Pubkey,
+    alice_token_x_account: Pubkey,
+    alice_token_y_account: Pubkey,
+    prize_vault: Pubkey,
+    fee_recipient: Pubkey,
+    treasury_token: Pubkey,
+}
+
+/// Creates a delegated house coin for alice, her stake left in her ata.
+async fn create_delegated_coin() -> Result<DelegatedCoin, Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        treasury_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        true,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // Nothing moved, the vault is only approved for the stake
+    let (vault, _) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+    let account = token_account(&alice_token_x_account.pubkey(), &mut ctx).await?;
+
+    assert_eq!(account.owner, alice.pubkey());
+    assert_eq!(account.amount, INITIATOR_FUNDS);
+    assert_eq!(account.delegate, COption::Some(vault));
+    assert_eq!(account.delegated_amount, AMOUNT);
+    assert_eq!(
+        token_balance(&degendevil::escrow_pda(&vault).0, &mut ctx).await?,
+        0
+    );
+
+    Ok(DelegatedCoin {
+        ctx,
+        alice,
+        oracle,
+        token_x: token_x.pubkey(),
+        token_y: token_y.pubkey(),
+        alice_token_x_account: alice_token_x_account.pubkey(),
+        alice_token_y_account: alice_token_y_account.pubkey(),
+        prize_vault,
+        fee_recipient,
+        treasury_token,
+    })
+}
+
+impl DelegatedCoin {
+    /// Flips the coin, handing over the ata and the escrow the stake is pulled into.
+    async fn flip(&mut self) -> CommandResult {
+        let (vault, _) = degendevil::vault_pda(&self.token_x, &self.alice.pubkey(), 0);
+
+        let mut ix = flip_coin_ix(
+            &self.alice.pubkey(),
+            &self.token_x,
+            0,
+            &self.oracle.pubkey(),
+        );
+        ix.accounts.extend([
+            AccountMeta::new(self.alice_token_x_account, false),
+            AccountMeta::new(degendevil::escrow_pda(&vault).0, false),
+        ]);
+
+        process_instruction(ix, &[&self.alice], &mut self.ctx).await
+    }
+
+    async fn reveal(&mut self, random: [u8; 64]) -> CommandResult {
+        let (coin, _) = degendevil::coin_pda(&self.alice.pubkey(), 0);
+        publish_random(&self.oracle, &coin, random, &mut self.ctx).await?;
+
+        let ix = reveal_coin_ix(
+            &self.alice.pubkey(),
+            &self.alice_token_x_account,
+            &self.fee_recipient,
+            &self.token_x,
+            &self.token_y,
+            &self.alice_token_y_account,
+            0,
+        );
+        process_instruction(ix, &[&self.alice], &mut self.ctx).await
+    }
+
+    async fn flip_and_reveal(&mut self, random: [u8; 64]) -> CommandResult {
+        self.flip().await?;
+        self.reveal(random).await
+    }
+}
+
+#[tokio::test]
+async fn delegated_loss_pulls_stake() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    // The highest roll loses at every tier
+    coin.flip_and_reveal([u8::MAX; 64]).await?;
+
+    let account = token_account(&coin.alice_token_x_account, &mut coin.ctx).await?;
+    assert_eq!(account.amount, INITIATOR_FUNDS - AMOUNT);
+    assert_eq!(account.delegate, COption::None);
+    assert_eq!(account.delegated_amount, 0);
+    assert_eq!(
+        token_balance(&coin.treasury_token, &mut coin.ctx).await?,
+        HOUSE_BANKROLL + AMOUNT
+    );
+
+    let (vault, _) = degendevil::vault_pda(&coin.token_x, &coin.alice.pubkey(), 0);
+    assert!(coin
+        .ctx
+        .banks_client
+        .get_account(degendevil::escrow_pda(&vault).0)
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flip_escrows_delegated_stake() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    coin.flip().await?;
+
+    // The stake is out of reach of alice from the request on, and the approval is gone
+    let account = token_account(&coin.alice_token_x_account, &mut coin.ctx).await?;
+    assert_eq!(account.amount, INITIATOR_FUNDS - AMOUNT);
+    assert_eq!(account.delegate, COption::None);
+
+    let (vault, _) = degendevil::vault_pda(&coin.token_x, &coin.alice.pubkey(), 0);
+    assert_eq!(
+        token_balance(&degendevil::escrow_pda(&vault).0, &mut coin.ctx).await?,
+        AMOUNT
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delegated_win_returns_stake() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    coin.flip_and_reveal([0u8; 64]).await?;
+
+    let receipt: degendevil::FlipReceipt = get_account(
+        &degendevil::receipt_pda(&coin.alice.pubkey(), 0).0,
+        &mut coin.ctx,
+    )
+    .await?;
+    assert!(receipt.initiator_won);
+
+    // The stake less fees comes back from the escrow next to the payout of the treasury
+    let settled = AMOUNT - receipt.fee - receipt.referral_fee - receipt.jackpot_contribution;
+    let account = token_account(&coin.alice_token_x_account, &mut coin.ctx).await?;
+    assert_eq!(
+        account.amount,
+        INITIATOR_FUNDS - AMOUNT + settled + receipt.payout + receipt.jackpot_won
+    );
+    assert_eq!(account.delegate, COption::None);
+    assert_eq!(
+        token_balance(&coin.treasury_token, &mut coin.ctx).await?,
+        HOUSE_BANKROLL - receipt.payout
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn second_delegated_coin_is_rejected() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    // Approving a second vault would silently revoke the first one
+    let ix = create_coin_ix(
+        &coin.alice.pubkey(),
+        &coin.alice_token_x_account,
+        &coin.token_x,
+        &coin.prize_vault,
+        &coin.oracle.pubkey(),
+        1,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        true,
+        None,
+    );
+    assert_eq!(
+        error_code(process_instruction(ix, &[&coin.alice], &mut coin.ctx).await),
+        Some(degendevil::DegenErrorCode::DelegateInUse.into())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn revoke_before_flip_is_rejected() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    let ix = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &coin.alice_token_x_account,
+        &coin.alice.pubkey(),
+        &[],
+    )?;
+    process_instruction(ix, &[&coin.alice], &mut coin.ctx).await?;
+
+    // A coin whose stake cannot be pulled is never played
+    assert_eq!(
+        error_code(coin.flip().await),
+        Some(degendevil::DegenErrorCode::DelegationRevoked.into())
+    );
+
+    // Nothing was requested, the coin is only left to be cancelled
+    let ix = cancel_coin_ix(
+        &coin.alice.pubkey(),
+        &coin.alice_token_x_account,
+        &coin.token_x,
+        0,
+    );
+    process_instruction(ix, &[&coin.alice], &mut coin.ctx).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn revoke_after_flip_still_loses() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    coin.flip().await?;
+
+    let ix = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &coin.alice_token_x_account,
+        &coin.alice.pubkey(),
+        &[],
+    )?;
+    process_instruction(ix, &[&coin.alice], &mut coin.ctx).await?;
+
+    // The highest roll loses at every tier, the escrowed stake goes to the treasury regardless
+    coin.reveal([u8::MAX; 64]).await?;
+
+    let receipt: degendevil::FlipReceipt = get_account(
+        &degendevil::receipt_pda(&coin.alice.pubkey(), 0).0,
+        &mut coin.ctx,
+    )
+    .await?;
+    assert!(!receipt.initiator_won);
+
+    assert_eq!(
+        token_balance(&coin.alice_token_x_account, &mut coin.ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+    assert_eq!(
+        token_balance(&coin.treasury_token, &mut coin.ctx).await?,
+        HOUSE_BANKROLL + AMOUNT - receipt.fee - receipt.referral_fee - receipt.jackpot_contribution
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_revokes_delegation() -> Result<(), Error> {
+    let mut coin = create_delegated_coin().await?;
+
+    let ix = cancel_coin_ix(
+        &coin.alice.pubkey(),
+        &coin.alice_token_x_account,
+        &coin.token_x,
+        0,
+    );
+    process_instruction(ix, &[&coin.alice], &mut coin.ctx).await?;
+
+    let account = token_account(&coin.alice_token_x_account, &mut coin.ctx).await?;
+    assert_eq!(account.amount, INITIATOR_FUNDS);
+    assert_eq!(account.delegate, COption::None);
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use {
+    solana_program_test::*,
+    solana_sdk::{pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 10000;
+const INITIATOR_FUNDS: u64 = 1000000;
+const BANKROLL: u64 = 1000000;
+/// Even odds, a win returns 1.98x
+const TARGET: u8 = 50;
+
+struct DiceOutcome {
+    initiator_stake: u64,
+    bankroll: u64,
+    receipt: degendevil::FlipReceipt,
+    stats: degendevil::PlayerStats,
+}
+
+/// Rolls a dice for alice under `target`, answering the oracle request with `random`.
+async fn dice_with_random(target: u8, random: [u8; 64]) -> Result<DiceOutcome, Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let admin_token_x_account =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL,
+        &mut ctx,
+    )
+    .await?;
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    init_tier_table(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        default_tiers(),
+        &mut ctx,
+    )
+    .await?;
+
+    let (_, treasury_token) =
+        init_treasury(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+
+    let ix = deposit_bankroll_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL,
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    init_mint_config(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        &Pubkey::default(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    let ix = create_dice_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        target,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // Dice are flipped as any house coin
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, random, &mut ctx).await?;
+
+    let ix = reveal_dice_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The initiator gets their ata back, the coin and vault are closed
+    let account = token_account(&alice_token_x_account.pubkey(), &mut ctx).await?;
+    assert_eq!(account.owner, alice.pubkey());
+    assert!(ctx.banks_client.get_account(coin).await?.is_none());
+
+    let (stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+
+    Ok(DiceOutcome {
+        initiator_stake: account.amount,
+        bankroll: token_balance(&treasury_token, &mut ctx).await?,
+        receipt: get_account(&degendevil::receipt_pda(&alice.pubkey(), 0).0, &mut ctx).await?,
+        stats: get_account(&stats, &mut ctx).await?,
+    })
+}
+
+#[tokio::test]
+async fn dice_win() -> Result<(), Error> {
+    // Rolls 0, under any target
+    let outcome = dice_with_random(TARGET, [0u8; 64]).await?;
+
+    // 1% is taken off the fair 2x of even odds
+    let payout = 9800;
+
+    assert!(outcome.receipt.initiator_won);
+    assert_eq!(outcome.receipt.dice_target, TARGET);
+    assert_eq!(outcome.receipt.win_bps, 5000);
+    assert_eq!(outcome.receipt.payout, payout);
+    assert_eq!(outcome.initiator_stake, INITIATOR_FUNDS + payout);
+    assert_eq!(outcome.bankroll, BANKROLL - payout);
+
+    assert_eq!(outcome.stats.wins, 1);
+    assert_eq!(outcome.stats.won, payout);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dice_loss() -> Result<(), Error> {
+    // Rolls 99, over any target
+    let outcome = dice_with_random(TARGET, [u8::MAX; 64]).await?;
+
+    assert!(!outcome.receipt.initiator_won);
+    assert_eq!(outcome.receipt.payout, 0);
+    assert_eq!(outcome.initiator_stake, INITIATOR_FUNDS - AMOUNT);
+    assert_eq!(outcome.bankroll, BANKROLL + AMOUNT);
+
+    assert_eq!(outcome.stats.losses, 1);
+    assert_eq!(outcome.stats.wagered, AMOUNT);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dice_long_shot() -> Result<(), Error> {
+    let outcome = dice_with_random(2, [0u8; 64]).await?;
+
+    // 49.5x at 2% odds
+    assert!(outcome.receipt.initiator_won);
+    assert_eq!(outcome.receipt.win_bps, 200);
+    assert_eq!(outcome.receipt.payout, 485000);
+    assert_eq!(outcome.initiator_stake, INITIATOR_FUNDS + 485000);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn dice_rejects_invalid_target_and_coin_reveal() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    init_prize_vault(&winner_mint_holder, &token_y.pubkey(), 0, &mut ctx).await?;
+
+    init_tier_table(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        default_tiers(),
+        &mut ctx,
+    )
+    .await?;
+
+    let (_, treasury_token) =
+        init_treasury(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+    init_jackpot(&winner_mint_holder, &token_x.pubkey(), 0, 0, &mut ctx).await?;
+    let fee_recipient =
+        init_fee_recipient(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+
+    init_mint_config(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    let create = |target: u8| {
+        create_dice_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &oracle.pubkey(),
+            0,
+            AMOUNT,
+            2,
+            target,
+        )
+    };
+
+    // Certain or hopeless rolls are not offered
+    for target in [0, 1, 99, 100] {
+        assert!(process_instruction(create(target), &[&alice], &mut ctx)
+            .await
+            .is_err());
+    }
+
+    // Nor are rolls the bankroll could not pay out
+    assert_eq!(
+        error_code(process_instruction(create(TARGET), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InsufficientBankroll.into())
+    );
+
+    mint_token(&token_x.pubkey(), &treasury_token, BANKROLL, &mut ctx).await?;
+
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    process_instruction(create(TARGET), &[&alice], &mut ctx).await?;
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [0u8; 64], &mut ctx).await?;
+
+    // A dice is never settled at the odds of the tiers
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    Ok(())
+}
@@ -0,0 +1,184 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{
+        hash::hash,
+        instruction::Instruction,
+        signature::{Keypair, Signer},
+    },
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+#[tokio::test]
+async fn coin_lifecycle_emits_events() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (requester, _) = degenrand::requestor_pda(&coin);
+
+    let created: Vec<degendevil::CoinCreated> = events();
+    let created = created.iter().find(|e| e.coin == coin).unwrap();
+    assert_eq!(created.initiator, alice.pubkey());
+    assert_eq!(created.mint, token_x.pubkey());
+    assert_eq!(created.amount, AMOUNT);
+    assert_eq!(created.nonce, 0);
+    assert!(!created.is_p2p && !created.is_sol);
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let requested: Vec<degenrand::RandomnessRequested> = events();
+    let requested = requested.iter().find(|e| e.requester == requester).unwrap();
+    assert_eq!(requested.authority, coin);
+    assert_eq!(requested.oracle, oracle.pubkey());
+    assert_eq!(requested.count, 1);
+
+    let random = [u8::MAX; 64];
+    publish_random(&oracle, &coin, random, &mut ctx).await?;
+
+    let published: Vec<degenrand::RandomnessPublished> = events();
+    let published = published.iter().find(|e| e.requester == requester).unwrap();
+    assert_eq!(published.oracle, oracle.pubkey());
+    assert_eq!(published.random, random);
+
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The highest roll loses at every tier
+    let revealed: Vec<degendevil::CoinRevealed> = events();
+    let revealed = revealed.iter().find(|e| e.coin == coin).unwrap();
+    assert_eq!(revealed.initiator, alice.pubkey());
+    assert_eq!(revealed.amount, AMOUNT);
+    assert!(!revealed.initiator_won);
+    assert_eq!(revealed.payout, 0);
+    assert_eq!(revealed.random_digest, hash(&random).to_bytes());
+
+    let removed: Vec<degenrand::PdasRemoved> = events();
+    let removed = removed.iter().find(|e| e.requester == requester).unwrap();
+    assert_eq!(removed.vault, degenrand::vault_pda(&coin).0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transfer_authority_emits_event() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        ..
+    } = get_program_test_context().await?;
+
+    // A requester owned by a wallet rather than a coin
+    let authority = Keypair::new();
+    let (requester, request_bump) = degenrand::requestor_pda(&authority.pubkey());
+    let (vault, vault_bump) = degenrand::vault_pda(&authority.pubkey());
+
+    let ix = Instruction {
+        program_id: degenrand::id(),
+        accounts: degenrand::accounts::Initialize {
+            requester,
+            vault,
+            authority: authority.pubkey(),
+            payer: alice.pubkey(),
+            oracle: oracle.pubkey(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degenrand::instruction::Initialize {
+            request_bump,
+            vault_bump,
+        }
+        .data(),
+    };
+    process_instruction(ix, &[&alice, &authority], &mut ctx).await?;
+
+    let ix = Instruction {
+        program_id: degenrand::id(),
+        accounts: degenrand::accounts::TransferAuthority {
+            requester,
+            authority: authority.pubkey(),
+            new_authority: bob.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degenrand::instruction::TransferAuthority {}.data(),
+    };
+    process_instruction(ix, &[&authority], &mut ctx).await?;
+
+    let transferred: Vec<degenrand::AuthorityTransferred> = events();
+    let transferred = transferred
+        .iter()
+        .find(|e| e.requester == requester)
+        .unwrap();
+    assert_eq!(transferred.old_authority, authority.pubkey());
+    assert_eq!(transferred.new_authority, bob.pubkey());
+
+    Ok(())
+}
@@ -0,0 +1,276 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use degendevil::DegenErrorCode;
+use solana_sdk::instruction::AccountMeta;
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+const FEE_BPS: u16 = 200;
+const REFERRAL_BPS: u16 = 2500;
+
+fn set_fees_ix(admin: &Pubkey, fee_bps: u16, referral_bps: u16) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetFees {
+            fee_bps,
+            referral_bps,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn settle_with_fees_and_referrer() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        treasury_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // Only the admin sets the fees, and never above the whole stake
+    let ix = set_fees_ix(&alice.pubkey(), FEE_BPS, REFERRAL_BPS);
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = set_fees_ix(&winner_mint_holder.pubkey(), 10001, REFERRAL_BPS);
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = set_fees_ix(&winner_mint_holder.pubkey(), FEE_BPS, REFERRAL_BPS);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // Bob referred alice
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        Some(bob_token_x_account.pubkey()),
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+    let reveal = || {
+        reveal_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &fee_recipient,
+            &token_x.pubkey(),
+            &token_y.pubkey(),
+            &alice_token_y_account.pubkey(),
+            0,
+        )
+    };
+
+    // The referrer cannot be left out
+    let ix = reveal();
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let mut ix = reveal();
+    ix.accounts
+        .push(AccountMeta::new(bob_token_x_account.pubkey(), false));
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // 2% of the stake is charged, a quarter of it goes to bob
+    assert_eq!(token_balance(&fee_recipient, &mut ctx).await?, 79);
+    assert_eq!(
+        token_balance(&bob_token_x_account.pubkey(), &mut ctx).await?,
+        26
+    );
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL + AMOUNT - 105
+    );
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+
+    let receipt: degendevil::FlipReceipt =
+        get_account(&degendevil::receipt_pda(&alice.pubkey(), 0).0, &mut ctx).await?;
+    assert_eq!(receipt.fee, 79);
+    assert_eq!(receipt.referral_fee, 26);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn referrer_is_checked_and_skipped_when_unpayable() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+    let bob_token_y_account = create_token_account(&token_y.pubkey(), &bob, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        treasury_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = set_fees_ix(&winner_mint_holder.pubkey(), FEE_BPS, REFERRAL_BPS);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let create = |referrer: &Pubkey| {
+        create_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            0,
+            AMOUNT,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            Some(*referrer),
+        )
+    };
+
+    // Alice cannot refer herself, nor name an account of another mint
+    for referrer in [alice_token_x_account.pubkey(), bob_token_y_account.pubkey()] {
+        let ix = create(&referrer);
+        assert_eq!(
+            error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+            Some(DegenErrorCode::InvalidReferrer.into())
+        );
+    }
+
+    // The referrer's account cannot be left out
+    let mut ix = create(&bob_token_x_account.pubkey());
+    ix.accounts.pop();
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(DegenErrorCode::InvalidReferrer.into())
+    );
+
+    let ix = create(&bob_token_x_account.pubkey());
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    // Bob closes his account before the coin is revealed
+    let ix = spl_token::instruction::close_account(
+        &spl_token::id(),
+        &bob_token_x_account.pubkey(),
+        &bob.pubkey(),
+        &bob.pubkey(),
+        &[],
+    )
+    .unwrap();
+    process_instruction(ix, &[&bob], &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+    let mut ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    ix.accounts
+        .push(AccountMeta::new(bob_token_x_account.pubkey(), false));
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The referral share goes to the fee recipient with the rest of the fee
+    assert_eq!(token_balance(&fee_recipient, &mut ctx).await?, 105);
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL + AMOUNT - 105
+    );
+
+    let receipt: degendevil::FlipReceipt =
+        get_account(&degendevil::receipt_pda(&alice.pubkey(), 0).0, &mut ctx).await?;
+    assert_eq!(receipt.fee, 105);
+    assert_eq!(receipt.referral_fee, 0);
+
+    Ok(())
+}
@@ -3,15 +3,37 @@
 mod utils;
 
 use anchor_lang::{InstructionData, ToAccountMetas};
-use solana_sdk::{instruction::AccountMeta, transaction::Transaction};
+use solana_sdk::instruction::AccountMeta;
 use {
     solana_program_test::*,
-    solana_sdk::{instruction::Instruction, signature::Signer},
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
     utils::*,
 };
 
-#[tokio::test]
-async fn flip() -> Result<(), Error> {
+const AMOUNT: u64 = 5250;
+const REWARD: u64 = 100000;
+const PRIZE_FUNDS: u64 = 52500000;
+const INITIATOR_FUNDS: u64 = 1000000;
+const BANKROLL: u64 = 1000000;
+
+struct FlipOutcome {
+    initiator_stake: u64,
+    bankroll: u64,
+    initiator_prize: u64,
+    prize_vault: u64,
+    receipt: degendevil::FlipReceipt,
+    stats: degendevil::PlayerStats,
+}
+
+/// Plays a single player coin for alice staking `amount` of a mint with `decimals` under
+/// `tiers`, answering the oracle request with `random`, and returns the Token A and Token B
+/// balances, the receipt and the statistics of alice once the coin is revealed.
+async fn flip_with_random(
+    decimals: u8,
+    amount: u64,
+    tiers: Vec<degendevil::Tier>,
+    random: [u8; 64],
+) -> Result<FlipOutcome, Error> {
     let TestContext {
         mut ctx,
         alice,
@@ -20,18 +42,21 @@ async fn flip() -> Result<(), Error> {
         ..
     } = get_program_test_context().await?;
 
-    let token_x = create_token(2, &mut ctx).await?;
+    let token_x = create_token(decimals, &mut ctx).await?;
     let token_y = create_token(0, &mut ctx).await?;
 
     let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
 
+    let admin_token_x_account =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
     let winner_token_y_account =
         create_token_account(&token_y.pubkey(), &winner_mint_holder, &mut ctx).await?;
 
     mint_token(
         &token_x.pubkey(),
         &alice_token_x_account.pubkey(),
-        1000000,
+        INITIATOR_FUNDS,
         &mut ctx,
     )
     .await?;
@@ -39,59 +64,94 @@ async fn flip() -> Result<(), Error> {
     mint_token(
         &token_y.pubkey(),
         &winner_token_y_account.pubkey(),
-        52500000,
+        PRIZE_FUNDS,
         &mut ctx,
     )
     .await?;
 
-    let amount = 5250;
+    mint_token(
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL,
+        &mut ctx,
+    )
+    .await?;
 
-    let (coin_pda, coin_bump) = degendevil::coin_pda(&alice.pubkey());
-    let (winner_pda, _winner_bum) = degendevil::winner_pda(&alice.pubkey());
-    let (vault_pda, vault_bump) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey());
+    // The holder of Token B runs the game and funds the prize vault
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
 
-    let (requester, req_bump) = degenrand::requestor_pda(&alice.pubkey());
-    let (oracle_vault, oracle_bump) = degenrand::vault_pda(&alice.pubkey());
+    let (prize_vault, prize_token) =
+        init_prize_vault(&winner_mint_holder, &token_y.pubkey(), REWARD, &mut ctx).await?;
 
-    let degenrand_init_accounts = degenrand::accounts::Initialize {
-        authority: alice.pubkey(),
-        oracle: oracle.pubkey(),
-        requester,
-        vault: oracle_vault,
-        rent: anchor_lang::solana_program::sysvar::rent::id(),
-        system_program: anchor_lang::solana_program::system_program::id(),
-    }
-    .to_account_metas(None);
+    let tier_table =
+        init_tier_table(&winner_mint_holder, &token_x.pubkey(), tiers, &mut ctx).await?;
 
-    let degenrand_init_data = degenrand::instruction::Initialize {
-        request_bump: req_bump,
-        vault_bump: oracle_bump,
-    }
-    .data();
+    let (treasury, treasury_token) =
+        init_treasury(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+
+    let (jackpot, jackpot_token) =
+        init_jackpot(&winner_mint_holder, &token_x.pubkey(), 0, 0, &mut ctx).await?;
+
+    let fee_recipient =
+        init_fee_recipient(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
 
-    let transaction = Transaction::new_signed_with_payer(
-        &[Instruction {
-            accounts: degenrand_init_accounts,
-            data: degenrand_init_data,
-            program_id: degenrand::id(),
-        }],
-        Some(&ctx.payer.pubkey()),
-        &[&ctx.payer, &alice],
-        ctx.last_blockhash,
+    let ix = deposit_bankroll_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL,
     );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    transfer_token(
+        &winner_token_y_account.pubkey(),
+        &prize_token,
+        &winner_mint_holder,
+        PRIZE_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    init_mint_config(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    let counter = init_counter(&alice, &mut ctx).await?;
 
-    ctx.banks_client.process_transaction(transaction).await?;
+    let (coin_pda, coin_bump) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (receipt_pda, _) = degendevil::receipt_pda(&alice.pubkey(), 0);
+    let (player_stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+    let (vault_pda, vault_bump) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+
+    let (requester, _) = degenrand::requestor_pda(&coin_pda);
+    let (oracle_vault, _) = degenrand::vault_pda(&coin_pda);
 
     let mut create_coin_accounts = degendevil::accounts::CreateCoin {
+        counter,
         coin: coin_pda,
         vault: vault_pda,
+        escrow: degendevil::escrow_pda(&vault_pda).0,
         initiator: alice.pubkey(),
         requester,
+        oracle_vault,
+        oracle: oracle.pubkey(),
         // acceptor: bob.pubkey(),
         initiator_ata: alice_token_x_account.pubkey(),
         mint: token_x.pubkey(),
-        oracle: oracle.pubkey(),
-        oracle_vault,
+        config: degendevil::config_pda().0,
+        mint_config: degendevil::mint_config_pda(&token_x.pubkey()).0,
+        tier_table,
+        treasury,
+        treasury_token,
+        jackpot,
+        prize_vault,
+        player_stats,
         degenrand_program: degenrand::id(),
         rent: anchor_lang::solana_program::sysvar::rent::id(),
         token_program: spl_token::id(),
@@ -104,8 +164,13 @@ async fn flip() -> Result<(), Error> {
     let create_coin_data = degendevil::instruction::CreateCoin {
         // _req_bump: req_bump,
         amount,
+        decimals,
         coin_bump,
         vault_bump,
+        p2p: false,
+        side: degendevil::HEADS,
+        delegated: false,
+        referrer: None,
     }
     .data();
 
@@ -115,78 +180,340 @@ async fn flip() -> Result<(), Error> {
         data: create_coin_data,
     };
 
-    let transaction = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&ctx.payer.pubkey()),
-        &[&ctx.payer, &alice],
-        ctx.last_blockhash,
-    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
 
-    ctx.banks_client.process_transaction(transaction).await?;
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
 
-    let mut publish_random_accounts = degenrand::accounts::PublishRandom {
-        oracle: oracle.pubkey(),
-
-        system_program: anchor_lang::solana_program::system_program::id(),
-    }
-    .to_account_metas(None);
-
-    publish_random_accounts.push(AccountMeta::new(requester, false));
-
-    let publish_random_data = degenrand::instruction::PublishRandom {
-        pkt_id: [0u8; 32],
-        random: [0u8; 64],
-        tls_id: [0u8; 32],
-    }
-    .data();
-
-    let ix = Instruction {
-        program_id: degenrand::id(),
-        accounts: publish_random_accounts,
-        data: publish_random_data,
-    };
-
-    let transaction = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&ctx.payer.pubkey()),
-        &[&ctx.payer, &oracle],
-        ctx.last_blockhash,
-    );
-
-    ctx.banks_client.process_transaction(transaction).await?;
+    publish_random(&oracle, &coin_pda, random, &mut ctx).await?;
 
     let reveal_coin_data = degendevil::instruction::RevealCoin {}.data();
 
-    let mut reveal_coin_accounts = degendevil::accounts::RevealCoin {
+    let reveal_coin_accounts = degendevil::accounts::RevealCoin {
         authority: alice.pubkey(),
         initiator: alice.pubkey(),
-        winner: winner_pda,
+        recipient: alice.pubkey(),
+        receipt: receipt_pda,
+        player_stats,
         initiator_ata: alice_token_x_account.pubkey(),
-        admin_ata: degendevil::admin_account_pubkey()?,
         mint: token_x.pubkey(),
+        coin: coin_pda,
         vault: vault_pda,
+        escrow: degendevil::escrow_pda(&vault_pda).0,
+        prize_vault,
+        prize_token,
+        initiator_prize_ata: alice_token_y_account.pubkey(),
+        treasury,
+        treasury_token,
+        jackpot,
+        jackpot_token,
+        config: degendevil::config_pda().0,
+        fee_recipient,
         requester,
+        oracle_vault,
         degenrand_program: degenrand::id(),
         token_program: spl_token::id(),
         system_program: anchor_lang::solana_program::system_program::id(),
     }
     .to_account_metas(None);
 
-    reveal_coin_accounts.push(AccountMeta::new(coin_pda, false));
-
     let ix = Instruction {
         program_id: degendevil::id(),
         accounts: reveal_coin_accounts,
         data: reveal_coin_data,
     };
-    let transaction = Transaction::new_signed_with_payer(
-        &[ix],
-        Some(&ctx.payer.pubkey()),
-        &[&ctx.payer, &alice],
-        ctx.last_blockhash,
+
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    Ok(FlipOutcome {
+        initiator_stake: token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        bankroll: token_balance(&treasury_token, &mut ctx).await?,
+        initiator_prize: token_balance(&alice_token_y_account.pubkey(), &mut ctx).await?,
+        prize_vault: token_balance(&prize_token, &mut ctx).await?,
+        receipt: get_account(&receipt_pda, &mut ctx).await?,
+        stats: get_account(&player_stats, &mut ctx).await?,
+    })
+}
+
+#[tokio::test]
+async fn flip_lose() -> Result<(), Error> {
+    // The highest possible roll never wins the 75% tier
+    let balances = flip_with_random(2, AMOUNT, default_tiers(), [u8::MAX; 64]).await?;
+
+    assert_eq!(balances.initiator_stake, INITIATOR_FUNDS - AMOUNT);
+    assert_eq!(balances.bankroll, BANKROLL + AMOUNT);
+    assert_eq!(balances.initiator_prize, 0);
+    assert_eq!(balances.prize_vault, PRIZE_FUNDS);
+
+    assert!(!balances.receipt.initiator_won);
+    assert_eq!(balances.receipt.win_bps, 7500);
+    assert_eq!(balances.receipt.random, [u8::MAX; 64]);
+    // A lost house coin lands on the side the initiator did not pick
+    assert_eq!(balances.receipt.side, degendevil::HEADS);
+    assert_eq!(balances.receipt.landed, degendevil::TAILS);
+
+    let stats = balances.stats;
+    assert_eq!((stats.flips, stats.wins, stats.losses), (1, 0, 1));
+    assert_eq!(stats.wagered, AMOUNT);
+    assert_eq!(stats.won, 0);
+    assert_eq!(stats.current_streak, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flip_win() -> Result<(), Error> {
+    // The lowest possible roll always wins the 75% tier
+    let balances = flip_with_random(2, AMOUNT, default_tiers(), [0u8; 64]).await?;
+
+    // A 75% tier pays a third of the stake from the bankroll
+    assert_eq!(balances.initiator_stake, INITIATOR_FUNDS + AMOUNT / 3);
+    assert_eq!(balances.bankroll, BANKROLL - AMOUNT / 3);
+    assert_eq!(balances.initiator_prize, REWARD);
+    assert_eq!(balances.prize_vault, PRIZE_FUNDS - REWARD);
+
+    let receipt = balances.receipt;
+    assert!(receipt.initiator_won);
+    assert_eq!(receipt.amount, AMOUNT);
+    assert_eq!(receipt.win_bps, 7500);
+    assert_eq!(receipt.payout, AMOUNT / 3);
+    assert_eq!(receipt.random, [0u8; 64]);
+    assert_eq!(receipt.landed, degendevil::HEADS);
+    assert_eq!(receipt.acceptor, Pubkey::default());
+
+    let stats = balances.stats;
+    assert_eq!(stats.player, receipt.initiator);
+    assert_eq!((stats.flips, stats.wins, stats.losses), (1, 1, 0));
+    assert_eq!(stats.wagered, AMOUNT);
+    assert_eq!(stats.won, AMOUNT / 3);
+    assert_eq!((stats.current_streak, stats.best_streak), (1, 1));
+    assert_eq!(stats.last_played, receipt.timestamp);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flip_uses_configured_tiers() -> Result<(), Error> {
+    // A promotion making every bet a sure win beats even the highest roll
+    let tiers = vec![degendevil::Tier {
+        min_amount: 0,
+        win_bps: 10000,
+    }];
+
+    let balances = flip_with_random(2, AMOUNT, tiers, [u8::MAX; 64]).await?;
+
+    // Certain wins keep the stake but earn nothing from the bankroll
+    assert_eq!(balances.initiator_stake, INITIATOR_FUNDS);
+    assert_eq!(balances.bankroll, BANKROLL);
+    assert_eq!(balances.initiator_prize, REWARD);
+    assert_eq!(balances.prize_vault, PRIZE_FUNDS - REWARD);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flip_tiers_follow_mint_decimals() -> Result<(), Error> {
+    // 53 base units of a mint without decimals are 53 tokens, well past the 52.5 token tier
+    let tiers = vec![degendevil::Tier {
+        min_amount: 5250,
+        win_bps: 10000,
+    }];
+
+    let balances = flip_with_random(0, 53, tiers, [u8::MAX; 64]).await?;
+
+    assert_eq!(balances.initiator_stake, INITIATOR_FUNDS);
+    assert_eq!(balances.initiator_prize, REWARD);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn create_coin_checks_decimals() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // A client treating the stake as a 9 decimal amount is turned away
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        9,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn flip_two_coins_at_once() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    // Each coin escrows its own Token A account
+    let first_stake = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let second_stake = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    for account in [&first_stake, &second_stake] {
+        mint_token(
+            &token_x.pubkey(),
+            &account.pubkey(),
+            INITIATOR_FUNDS,
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let House {
+        prize_vault,
+        treasury_token,
+        fee_recipient,
+        counter,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    for (nonce, stake) in [&first_stake, &second_stake].iter().enumerate() {
+        let ix = create_coin_ix(
+            &alice.pubkey(),
+            &stake.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce as u64,
+            AMOUNT,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        );
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+
+        flip_coin(
+            &alice,
+            &token_x.pubkey(),
+            nonce as u64,
+            &oracle.pubkey(),
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let counter: degendevil::CoinCounter = get_account(&counter, &mut ctx).await?;
+    assert_eq!(counter.count, 2);
+
+    // Both requests are in flight, the oracle answers them out of order
+    for (nonce, stake) in [&first_stake, &second_stake].iter().enumerate().rev() {
+        let (coin, _) = degendevil::coin_pda(&alice.pubkey(), nonce as u64);
+
+        publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+        let ix = reveal_coin_ix(
+            &alice.pubkey(),
+            &stake.pubkey(),
+            &fee_recipient,
+            &token_x.pubkey(),
+            &token_y.pubkey(),
+            &alice_token_y_account.pubkey(),
+            nonce as u64,
+        );
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+
+        assert!(ctx.banks_client.get_account(coin).await?.is_none());
+
+        // Every flip keeps its own receipt
+        let (receipt, _) = degendevil::receipt_pda(&alice.pubkey(), nonce as u64);
+        let receipt: degendevil::FlipReceipt = get_account(&receipt, &mut ctx).await?;
+
+        assert_eq!(receipt.coin, coin);
+        assert_eq!(receipt.nonce, nonce as u64);
+        assert_eq!(receipt.mint, token_x.pubkey());
+    }
+
+    for stake in [&first_stake, &second_stake] {
+        let account = token_account(&stake.pubkey(), &mut ctx).await?;
+
+        assert_eq!(account.owner, alice.pubkey());
+        assert_eq!(account.amount, INITIATOR_FUNDS - AMOUNT);
+    }
+
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL + 2 * AMOUNT
     );
 
-    ctx.banks_client.process_transaction(transaction).await?;
+    // Both coins share the statistics of alice in the mint
+    let (stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+    let stats: degendevil::PlayerStats = get_account(&stats, &mut ctx).await?;
+    assert_eq!((stats.flips, stats.losses), (2, 2));
+    assert_eq!(stats.wagered, 2 * AMOUNT);
 
     Ok(())
 }
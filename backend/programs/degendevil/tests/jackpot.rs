@@ -0,0 +1,301 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+const CONTRIBUTION_BPS: u16 = 100;
+const ODDS_PPM: u32 = 1000;
+
+fn set_jackpot_ix(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    contribution_bps: u16,
+    odds_ppm: u32,
+) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::SetJackpot {
+            config: degendevil::config_pda().0,
+            jackpot: degendevil::jackpot_pda(mint).0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetJackpot {
+            contribution_bps,
+            odds_ppm,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn grow_and_hit_jackpot() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        treasury_token,
+        jackpot_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // Only the admin tunes the jackpot, and never beyond certainty
+    let ix = set_jackpot_ix(
+        &alice.pubkey(),
+        &token_x.pubkey(),
+        CONTRIBUTION_BPS,
+        ODDS_PPM,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = set_jackpot_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        CONTRIBUTION_BPS,
+        1_000_001,
+    );
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = set_jackpot_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        CONTRIBUTION_BPS,
+        ODDS_PPM,
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // Both flips are lost, only the second one hits the jackpot
+    let mut hit = [u8::MAX; 64];
+    hit[32..].copy_from_slice(&[0u8; 32]);
+
+    for (nonce, random) in [[u8::MAX; 64], hit].iter().enumerate() {
+        let nonce = nonce as u64;
+
+        let ix = create_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce,
+            AMOUNT,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        );
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+
+        flip_coin(&alice, &token_x.pubkey(), nonce, &oracle.pubkey(), &mut ctx).await?;
+
+        let (coin, _) = degendevil::coin_pda(&alice.pubkey(), nonce);
+        publish_random(&oracle, &coin, *random, &mut ctx).await?;
+
+        let ix = reveal_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &fee_recipient,
+            &token_x.pubkey(),
+            &token_y.pubkey(),
+            &alice_token_y_account.pubkey(),
+            nonce,
+        );
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+
+        if nonce == 0 {
+            // 1% of the lost stake grows the pool, the rest goes to the house
+            assert_eq!(token_balance(&jackpot_token, &mut ctx).await?, 52);
+            assert_eq!(
+                token_balance(&treasury_token, &mut ctx).await?,
+                HOUSE_BANKROLL + AMOUNT - 52
+            );
+        }
+    }
+
+    // The hit pays out the whole pool, including the slice of the winning stake
+    assert_eq!(token_balance(&jackpot_token, &mut ctx).await?, 0);
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        HOUSE_BANKROLL + 2 * (AMOUNT - 52)
+    );
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - 2 * AMOUNT + 104
+    );
+
+    let receipt: degendevil::FlipReceipt =
+        get_account(&degendevil::receipt_pda(&alice.pubkey(), 1).0, &mut ctx).await?;
+    assert_eq!(receipt.jackpot_contribution, 52);
+    assert_eq!(receipt.jackpot_won, 104);
+
+    let (stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+    let stats: degendevil::PlayerStats = get_account(&stats, &mut ctx).await?;
+    assert_eq!(stats.losses, 2);
+    assert_eq!(stats.won, 104);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn p2p_jackpot_goes_to_winner() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+
+    for account in [&alice_token_x_account, &bob_token_x_account] {
+        mint_token(
+            &token_x.pubkey(),
+            &account.pubkey(),
+            INITIATOR_FUNDS,
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let House {
+        prize_vault,
+        jackpot_token,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = set_jackpot_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        CONTRIBUTION_BPS,
+        ODDS_PPM,
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        true,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let ix = accept_coin_ix(
+        &alice.pubkey(),
+        &bob.pubkey(),
+        &bob_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &oracle.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&bob], &mut ctx).await?;
+
+    // The coin lands tails against alice's heads and hits the jackpot
+    let mut random = [0u8; 64];
+    random[0] = 1;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, random, &mut ctx).await?;
+
+    let ix = reveal_p2p_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &bob.pubkey(),
+        &bob_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // Bob wins the pot and the pool it grew, alice only loses her stake
+    assert_eq!(token_balance(&jackpot_token, &mut ctx).await?, 0);
+    assert_eq!(
+        token_balance(&bob_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS + AMOUNT
+    );
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+
+    let receipt: degendevil::FlipReceipt =
+        get_account(&degendevil::receipt_pda(&alice.pubkey(), 0).0, &mut ctx).await?;
+    assert!(!receipt.initiator_won);
+    assert_eq!(receipt.jackpot_won, 52);
+
+    let (stats, _) = degendevil::player_stats_pda(&alice.pubkey(), &token_x.pubkey());
+    let stats: degendevil::PlayerStats = get_account(&stats, &mut ctx).await?;
+    assert_eq!(stats.won, 0);
+
+    let (stats, _) = degendevil::player_stats_pda(&bob.pubkey(), &token_x.pubkey());
+    let stats: degendevil::PlayerStats = get_account(&stats, &mut ctx).await?;
+    assert_eq!(stats.wins, 1);
+    assert_eq!(stats.won, AMOUNT);
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const INITIATOR_FUNDS: u64 = 1000000;
+const MIN_BET: u64 = 700;
+const MAX_BET: u64 = 5250;
+
+fn set_mint_config_ix(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    enabled: bool,
+    min_bet: u64,
+    max_bet: u64,
+    prize_mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::SetMintConfig {
+            config: degendevil::config_pda().0,
+            mint_config: degendevil::mint_config_pda(mint).0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetMintConfig {
+            enabled,
+            min_bet,
+            max_bet,
+            prize_mint: *prize_mint,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn only_allowed_mints_are_staked() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+    let token_z = create_token(2, &mut ctx).await?;
+    let token_w = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_z_account = create_token_account(&token_z.pubkey(), &alice, &mut ctx).await?;
+
+    for (mint, account) in [
+        (&token_x, &alice_token_x_account),
+        (&token_z, &alice_token_z_account),
+    ] {
+        mint_token(&mint.pubkey(), &account.pubkey(), INITIATOR_FUNDS, &mut ctx).await?;
+    }
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    let (prize_vault, _) =
+        init_prize_vault(&winner_mint_holder, &token_y.pubkey(), 0, &mut ctx).await?;
+    let (other_prize_vault, _) =
+        init_prize_vault(&winner_mint_holder, &token_w.pubkey(), 0, &mut ctx).await?;
+
+    for mint in [&token_x, &token_z] {
+        init_tier_table(
+            &winner_mint_holder,
+            &mint.pubkey(),
+            default_tiers(),
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let mint_config = init_mint_config(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        MIN_BET,
+        MAX_BET,
+        &mut ctx,
+    )
+    .await?;
+
+    let stored: degendevil::MintConfig = get_account(&mint_config, &mut ctx).await?;
+    assert!(stored.enabled);
+    assert_eq!(
+        stored.tier_table,
+        degendevil::tier_table_pda(&token_x.pubkey()).0
+    );
+    assert_eq!(stored.prize_mint, token_y.pubkey());
+
+    init_counter(&alice, &mut ctx).await?;
+
+    let create = |initiator_ata: &Pubkey, mint: &Pubkey, prize_vault: &Pubkey, amount: u64| {
+        create_coin_ix(
+            &alice.pubkey(),
+            initiator_ata,
+            mint,
+            prize_vault,
+            &oracle.pubkey(),
+            0,
+            amount,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        )
+    };
+
+    // A coin is only created once every account settling it exists
+    let ix = create(
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        MAX_BET,
+    );
+    assert_eq!(
+        error_code(process_instruction(ix.clone(), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InvalidFeeRecipient.into())
+    );
+
+    init_fee_recipient(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(anchor_lang::error::ErrorCode::AccountNotInitialized.into())
+    );
+
+    init_treasury(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+    init_jackpot(&winner_mint_holder, &token_x.pubkey(), 0, 0, &mut ctx).await?;
+
+    // The odds are the ones the admin registered for the mint
+    let mut ix = create(
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        MAX_BET,
+    );
+    let tier_table = degendevil::tier_table_pda(&token_x.pubkey()).0;
+    ix.accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == tier_table)
+        .unwrap()
+        .pubkey = degendevil::tier_table_pda(&token_z.pubkey()).0;
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InvalidTierTable.into())
+    );
+
+    // A mint the admin never allowed cannot be staked
+    let ix = create(
+        &alice_token_z_account.pubkey(),
+        &token_z.pubkey(),
+        &prize_vault,
+        MAX_BET,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // Nor can a stake outside the limits of an allowed one
+    for amount in [MIN_BET - 1, MAX_BET + 1] {
+        let ix = create(
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            amount,
+        );
+        assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+    }
+
+    // The coin is played for the payout mint of its mint only
+    let ix = create(
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &other_prize_vault,
+        MAX_BET,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // Only the admin changes the limits, which must stay ordered
+    let ix = set_mint_config_ix(
+        &alice.pubkey(),
+        &token_x.pubkey(),
+        false,
+        MIN_BET,
+        MAX_BET,
+        &token_y.pubkey(),
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = set_mint_config_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        true,
+        MAX_BET + 1,
+        MAX_BET,
+        &token_y.pubkey(),
+    );
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    // A disabled mint turns every coin away
+    let ix = set_mint_config_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        false,
+        MIN_BET,
+        MAX_BET,
+        &token_y.pubkey(),
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let ix = create(
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        MAX_BET,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = set_mint_config_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        true,
+        MIN_BET,
+        MAX_BET,
+        &token_y.pubkey(),
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // The same transaction was turned away above, it must not share its signature
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    let ix = create(
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        MAX_BET,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    Ok(())
+}
@@ -0,0 +1,253 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use degendevil::DegenErrorCode;
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+fn set_paused_ix(admin: &Pubkey, paused: bool) -> Instruction {
+    let data = if paused {
+        degendevil::instruction::Pause {}.data()
+    } else {
+        degendevil::instruction::Unpause {}.data()
+    };
+
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data,
+    }
+}
+
+#[tokio::test]
+async fn pause_blocks_new_coins_only() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        config,
+        prize_vault,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let create = |nonce: u64| {
+        create_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce,
+            AMOUNT,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        )
+    };
+
+    // One coin waits for the oracle, the other one was never flipped
+    process_instruction(create(0), &[&alice], &mut ctx).await?;
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+    process_instruction(create(1), &[&alice], &mut ctx).await?;
+
+    // Only the admin pulls the switch
+    let ix = set_paused_ix(&alice.pubkey(), true);
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = set_paused_ix(&winner_mint_holder.pubkey(), true);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let stored: degendevil::GameConfig = get_account(&config, &mut ctx).await?;
+    assert!(stored.paused);
+
+    assert!(process_instruction(create(2), &[&alice], &mut ctx)
+        .await
+        .is_err());
+
+    // Players still get out of the coins they already have
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let ix = cancel_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        1,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+
+    let ix = set_paused_ix(&winner_mint_holder.pubkey(), false);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // The same transaction was turned away above, it must not share its signature
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    process_instruction(create(2), &[&alice], &mut ctx).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn pause_blocks_flip_and_accept() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+
+    for account in [&alice_token_x_account, &bob_token_x_account] {
+        mint_token(
+            &token_x.pubkey(),
+            &account.pubkey(),
+            INITIATOR_FUNDS,
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // A house coin waiting to be flipped and an offer waiting for bob
+    for (nonce, p2p) in [(0, false), (1, true)] {
+        let ix = create_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce,
+            AMOUNT,
+            2,
+            p2p,
+            degendevil::HEADS,
+            false,
+            None,
+        );
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+    }
+
+    let ix = set_paused_ix(&winner_mint_holder.pubkey(), true);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let flip = || flip_coin_ix(&alice.pubkey(), &token_x.pubkey(), 0, &oracle.pubkey());
+    let accept = || {
+        accept_coin_ix(
+            &alice.pubkey(),
+            &bob.pubkey(),
+            &bob_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &oracle.pubkey(),
+            1,
+        )
+    };
+
+    // No stake is put in play until the game resumes
+    assert_eq!(
+        error_code(process_instruction(flip(), &[&alice], &mut ctx).await),
+        Some(DegenErrorCode::Paused.into())
+    );
+    assert_eq!(
+        error_code(process_instruction(accept(), &[&bob], &mut ctx).await),
+        Some(DegenErrorCode::Paused.into())
+    );
+    assert_eq!(
+        token_balance(&bob_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS
+    );
+
+    let ix = set_paused_ix(&winner_mint_holder.pubkey(), false);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // The same transactions were turned away above, they must not share their signatures
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    process_instruction(flip(), &[&alice], &mut ctx).await?;
+    process_instruction(accept(), &[&bob], &mut ctx).await?;
+
+    assert_eq!(
+        token_balance(&bob_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS - AMOUNT
+    );
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+fn refund_expired_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    mint: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::RefundExpired {
+            config: degendevil::config_pda().0,
+            coin,
+            vault: degendevil::vault_pda(mint, initiator, nonce).0,
+            escrow: degendevil::escrow_pda(&degendevil::vault_pda(mint, initiator, nonce).0).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            initiator: *initiator,
+            recipient: *initiator,
+            initiator_ata: *initiator_ata,
+            treasury: degendevil::treasury_pda(mint).0,
+            authority: *initiator,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::RefundExpired {}.data(),
+    }
+}
+
+#[tokio::test]
+async fn refund_expired_flip() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let (coin_pda, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (vault_pda, _) = degendevil::vault_pda(&token_x.pubkey(), &alice.pubkey(), 0);
+    let (requester, _) = degenrand::requestor_pda(&coin_pda);
+    let (oracle_vault, _) = degenrand::vault_pda(&coin_pda);
+
+    let alice_balance = ctx.banks_client.get_balance(alice.pubkey()).await?;
+    let oracle_balance = ctx.banks_client.get_balance(oracle.pubkey()).await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &token_x.pubkey(), 0, &oracle.pubkey(), &mut ctx).await?;
+
+    // The oracle never answers. Before the timeout the flip stays locked.
+    let ix = refund_expired_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    warp_clock(REFUND_TIMEOUT, &mut ctx).await?;
+
+    let ix = refund_expired_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let alice_account = token_account(&alice_token_x_account.pubkey(), &mut ctx).await?;
+    assert_eq!(alice_account.owner, alice.pubkey());
+    assert_eq!(alice_account.amount, INITIATOR_FUNDS);
+
+    assert!(ctx.banks_client.get_account(coin_pda).await?.is_none());
+    assert!(ctx.banks_client.get_account(vault_pda).await?.is_none());
+    assert!(ctx
+        .banks_client
+        .get_account(degendevil::escrow_pda(&vault_pda).0)
+        .await?
+        .is_none());
+    assert!(ctx.banks_client.get_account(requester).await?.is_none());
+    assert!(ctx.banks_client.get_account(oracle_vault).await?.is_none());
+
+    // Rent and the oracle fee are back with alice, the oracle was never paid
+    assert_eq!(
+        ctx.banks_client.get_balance(alice.pubkey()).await?,
+        alice_balance
+    );
+    assert_eq!(
+        ctx.banks_client.get_balance(oracle.pubkey()).await?,
+        oracle_balance
+    );
+
+    // A late answer from the oracle is rejected
+    assert!(publish_random(&oracle, &coin_pda, [0u8; 64], &mut ctx)
+        .await
+        .is_err());
+
+    Ok(())
+}
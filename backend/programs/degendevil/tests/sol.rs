@@ -0,0 +1,293 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use {
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Signer,
+    },
+    utils::*,
+};
+
+/// 52.5 SOL, in the 75% tier
+const AMOUNT: u64 = 52 * LAMPORTS_PER_SOL + LAMPORTS_PER_SOL / 2;
+const BANKROLL: u64 = 100 * LAMPORTS_PER_SOL;
+
+struct SolOutcome {
+    treasury_delta: i128,
+    receipt: degendevil::FlipReceipt,
+    stats: degendevil::PlayerStats,
+}
+
+async fn sol_flip_with_random(random: [u8; 64]) -> Result<SolOutcome, Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let native_mint = spl_token::native_mint::id();
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+    init_tier_table(&winner_mint_holder, &native_mint, default_tiers(), &mut ctx).await?;
+
+    let treasury = init_sol_treasury(&winner_mint_holder, BANKROLL, &mut ctx).await?;
+
+    init_mint_config(
+        &winner_mint_holder,
+        &native_mint,
+        &Pubkey::default(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    let ix = create_sol_coin_ix(&alice.pubkey(), &oracle.pubkey(), 0, AMOUNT);
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The stake sits in the vault on top of its rent
+    let (vault, _) = degendevil::vault_pda(&native_mint, &alice.pubkey(), 0);
+    let rent = ctx.banks_client.get_rent().await?;
+    let vault_rent = rent.minimum_balance(8 + std::mem::size_of::<degendevil::Vault>());
+
+    assert_eq!(
+        ctx.banks_client.get_balance(vault).await?,
+        vault_rent + AMOUNT
+    );
+
+    flip_coin(&alice, &native_mint, 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, random, &mut ctx).await?;
+
+    let treasury_before = ctx.banks_client.get_balance(treasury).await?;
+
+    let ix = reveal_sol_coin_ix(&alice.pubkey(), 0);
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    assert!(ctx.banks_client.get_account(coin).await?.is_none());
+    assert!(ctx.banks_client.get_account(vault).await?.is_none());
+
+    let treasury_after = ctx.banks_client.get_balance(treasury).await?;
+
+    Ok(SolOutcome {
+        treasury_delta: treasury_after as i128 - treasury_before as i128,
+        receipt: get_account(&degendevil::receipt_pda(&alice.pubkey(), 0).0, &mut ctx).await?,
+        stats: get_account(
+            &degendevil::player_stats_pda(&alice.pubkey(), &native_mint).0,
+            &mut ctx,
+        )
+        .await?,
+    })
+}
+
+#[tokio::test]
+async fn sol_flip_win() -> Result<(), Error> {
+    let outcome = sol_flip_with_random([0u8; 64]).await?;
+
+    // The treasury pays the odds of the tier, the stake is closed back with the vault
+    assert!(outcome.receipt.initiator_won);
+    assert_eq!(outcome.receipt.win_bps, 7500);
+    assert_eq!(outcome.receipt.mint, spl_token::native_mint::id());
+    assert_eq!(outcome.receipt.payout, AMOUNT / 3);
+    assert_eq!(outcome.treasury_delta, -((AMOUNT / 3) as i128));
+
+    assert_eq!(outcome.stats.wins, 1);
+    assert_eq!(outcome.stats.won, AMOUNT / 3);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sol_flip_loss() -> Result<(), Error> {
+    let outcome = sol_flip_with_random([u8::MAX; 64]).await?;
+
+    assert!(!outcome.receipt.initiator_won);
+    assert_eq!(outcome.receipt.payout, 0);
+    assert_eq!(outcome.treasury_delta, AMOUNT as i128);
+
+    assert_eq!(outcome.stats.losses, 1);
+    assert_eq!(outcome.stats.wagered, AMOUNT);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sol_coin_beyond_bankroll_is_rejected() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let native_mint = spl_token::native_mint::id();
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+    init_tier_table(&winner_mint_holder, &native_mint, default_tiers(), &mut ctx).await?;
+    let treasury = init_sol_treasury(&winner_mint_holder, AMOUNT / 3 - 1, &mut ctx).await?;
+    init_mint_config(
+        &winner_mint_holder,
+        &native_mint,
+        &Pubkey::default(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    // The 75% tier pays a third of the stake, one lamport more than the bankroll holds
+    let ix = create_sol_coin_ix(&alice.pubkey(), &oracle.pubkey(), 0, AMOUNT);
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InsufficientBankroll.into())
+    );
+
+    let stored: degendevil::Treasury = get_account(&treasury, &mut ctx).await?;
+    assert_eq!(stored.liability, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sol_coin_is_not_revealed_as_token_coin() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let native_mint = spl_token::native_mint::id();
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+    init_tier_table(&winner_mint_holder, &native_mint, default_tiers(), &mut ctx).await?;
+    init_sol_treasury(&winner_mint_holder, BANKROLL, &mut ctx).await?;
+    init_mint_config(
+        &winner_mint_holder,
+        &native_mint,
+        &Pubkey::default(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    let ix = create_sol_coin_ix(&alice.pubkey(), &oracle.pubkey(), 0, AMOUNT);
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    flip_coin(&alice, &native_mint, 0, &oracle.pubkey(), &mut ctx).await?;
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    publish_random(&oracle, &coin, [0u8; 64], &mut ctx).await?;
+
+    // The initiator stands in for the token account of a SOL coin
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice.pubkey(),
+        &Pubkey::new_unique(),
+        &native_mint,
+        &native_mint,
+        &alice.pubkey(),
+        0,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wrapped_sol_is_not_staked_as_token() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let native_mint = spl_token::native_mint::id();
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_wrapped_sol_account = create_token_account(&native_mint, &alice, &mut ctx).await?;
+
+    // Every account of the native mint exists, only the mint itself is refused
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+    let (prize_vault, _) =
+        init_prize_vault(&winner_mint_holder, &token_y.pubkey(), 0, &mut ctx).await?;
+    init_tier_table(&winner_mint_holder, &native_mint, default_tiers(), &mut ctx).await?;
+    init_sol_treasury(&winner_mint_holder, 0, &mut ctx).await?;
+    init_jackpot(&winner_mint_holder, &native_mint, 0, 0, &mut ctx).await?;
+    init_mint_config(
+        &winner_mint_holder,
+        &native_mint,
+        &token_y.pubkey(),
+        0,
+        u64::MAX,
+        &mut ctx,
+    )
+    .await?;
+
+    init_counter(&alice, &mut ctx).await?;
+
+    // The SOL treasury holds no token account, any other one stands in for it
+    let treasury_token = degendevil::treasury_token_pda(&native_mint).0;
+    let stand_in = |mut ix: Instruction| {
+        let meta = ix
+            .accounts
+            .iter_mut()
+            .find(|meta| meta.pubkey == treasury_token)
+            .unwrap();
+        meta.pubkey = alice_wrapped_sol_account.pubkey();
+
+        ix
+    };
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_wrapped_sol_account.pubkey(),
+        &native_mint,
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        spl_token::native_mint::DECIMALS,
+        false,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    assert_eq!(
+        error_code(process_instruction(stand_in(ix), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::NativeMint.into())
+    );
+
+    let ix = create_dice_ix(
+        &alice.pubkey(),
+        &alice_wrapped_sol_account.pubkey(),
+        &native_mint,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        spl_token::native_mint::DECIMALS,
+        50,
+    );
+    assert_eq!(
+        error_code(process_instruction(stand_in(ix), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::NativeMint.into())
+    );
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+fn set_tiers_ix(admin: &Pubkey, tier_table: &Pubkey, tiers: Vec<degendevil::Tier>) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::SetTiers {
+            config: degendevil::config_pda().0,
+            tier_table: *tier_table,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetTiers { tiers }.data(),
+    }
+}
+
+#[tokio::test]
+async fn set_tiers() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    let tier_table = init_tier_table(
+        &winner_mint_holder,
+        &token_x.pubkey(),
+        default_tiers(),
+        &mut ctx,
+    )
+    .await?;
+
+    let promotion = vec![
+        degendevil::Tier {
+            min_amount: 100,
+            win_bps: 4750,
+        },
+        degendevil::Tier {
+            min_amount: 5000,
+            win_bps: 9000,
+        },
+    ];
+
+    // Only the admin may change the odds
+    let ix = set_tiers_ix(&alice.pubkey(), &tier_table, promotion.clone());
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // Tiers out of order are rejected
+    let mut unordered = promotion.clone();
+    unordered.reverse();
+    let ix = set_tiers_ix(&winner_mint_holder.pubkey(), &tier_table, unordered);
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = set_tiers_ix(&winner_mint_holder.pubkey(), &tier_table, promotion);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    let table: degendevil::TierTable = get_account(&tier_table, &mut ctx).await?;
+    assert_eq!(table.mint, token_x.pubkey());
+    assert_eq!(table.tiers.len(), 2);
+    assert_eq!(table.tiers[0].win_bps, 4750);
+    assert_eq!(table.tiers[1].min_amount, 5000);
+
+    // Nobody but the admin can open a table for another mint either
+    let token_z = create_token(0, &mut ctx).await?;
+    assert!(
+        init_tier_table(&alice, &token_z.pubkey(), default_tiers(), &mut ctx)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const BANKROLL: u64 = 1000000;
+
+fn withdraw_bankroll_ix(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::WithdrawBankroll {
+            config: degendevil::config_pda().0,
+            treasury: degendevil::treasury_pda(mint).0,
+            treasury_token: degendevil::treasury_token_pda(mint).0,
+            destination: *destination,
+            admin: *admin,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::WithdrawBankroll { amount }.data(),
+    }
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw_bankroll() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+
+    let admin_token_x_account =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    for account in [&admin_token_x_account, &alice_token_x_account] {
+        mint_token(&token_x.pubkey(), &account.pubkey(), BANKROLL, &mut ctx).await?;
+    }
+
+    initialize_config(&winner_mint_holder, &oracle.pubkey(), &mut ctx).await?;
+
+    // Only the admin opens a treasury
+    assert!(init_treasury(&alice, &token_x.pubkey(), &mut ctx)
+        .await
+        .is_err());
+
+    let (treasury, treasury_token) =
+        init_treasury(&winner_mint_holder, &token_x.pubkey(), &mut ctx).await?;
+
+    let treasury: degendevil::Treasury = get_account(&treasury, &mut ctx).await?;
+    assert_eq!(treasury.mint, token_x.pubkey());
+    assert_eq!(treasury.treasury_token, treasury_token);
+
+    // Nor may anyone else fund it
+    let ix = deposit_bankroll_ix(
+        &alice.pubkey(),
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        BANKROLL,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    let ix = deposit_bankroll_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL,
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    assert_eq!(token_balance(&treasury_token, &mut ctx).await?, BANKROLL);
+
+    // Or drain it
+    let ix = withdraw_bankroll_ix(
+        &alice.pubkey(),
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        BANKROLL,
+    );
+    assert!(process_instruction(ix, &[&alice], &mut ctx).await.is_err());
+
+    // The admin cannot take more than the bankroll holds
+    let ix = withdraw_bankroll_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL + 1,
+    );
+    assert!(process_instruction(ix, &[&winner_mint_holder], &mut ctx)
+        .await
+        .is_err());
+
+    let ix = withdraw_bankroll_ix(
+        &winner_mint_holder.pubkey(),
+        &token_x.pubkey(),
+        &admin_token_x_account.pubkey(),
+        BANKROLL / 4,
+    );
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    assert_eq!(
+        token_balance(&treasury_token, &mut ctx).await?,
+        BANKROLL - BANKROLL / 4
+    );
+    assert_eq!(
+        token_balance(&admin_token_x_account.pubkey(), &mut ctx).await?,
+        BANKROLL / 4
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn house_coins_reserve_their_payout() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let admin_token_x_account =
+        create_token_account(&token_x.pubkey(), &winner_mint_holder, &mut ctx).await?;
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        BANKROLL,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        treasury,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    // A stake in the 10% tier is paid nine times over on a win
+    let create_coin = |nonce| {
+        create_coin_ix(
+            &alice.pubkey(),
+            &alice_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce,
+            1000,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        )
+    };
+
+    process_instruction(create_coin(0), &[&alice], &mut ctx).await?;
+
+    let stored: degendevil::Treasury = get_account(&treasury, &mut ctx).await?;
+    assert_eq!(stored.liability, 9000);
+
+    // The reserved payout cannot be withdrawn
+    let withdraw = |amount| {
+        withdraw_bankroll_ix(
+            &winner_mint_holder.pubkey(),
+            &token_x.pubkey(),
+            &admin_token_x_account.pubkey(),
+            amount,
+        )
+    };
+
+    let ix = withdraw(HOUSE_BANKROLL);
+    assert_eq!(
+        error_code(process_instruction(ix, &[&winner_mint_holder], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InsufficientFunds.into())
+    );
+
+    let ix = withdraw(HOUSE_BANKROLL - 9100);
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // Nor covers a second coin once the rest of the bankroll is gone
+    assert_eq!(
+        error_code(process_instruction(create_coin(1), &[&alice], &mut ctx).await),
+        Some(degendevil::DegenErrorCode::InsufficientBankroll.into())
+    );
+
+    // Cancelling the first coin frees its payout for the next one
+    let ix = cancel_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    let stored: degendevil::Treasury = get_account(&treasury, &mut ctx).await?;
+    assert_eq!(stored.liability, 0);
+
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    process_instruction(create_coin(1), &[&alice], &mut ctx).await?;
+
+    Ok(())
+}
@@ -1,29 +1,90 @@
+#![allow(dead_code)]
+
 use degendevil::id;
 
 use {
-    anchor_lang::solana_program::system_instruction::create_account,
+    anchor_lang::{
+        solana_program::system_instruction::create_account, AccountDeserialize, Event,
+        InstructionData, ToAccountMetas,
+    },
+    once_cell::sync::Lazy,
     solana_program_test::*,
     solana_sdk::{
         account::AccountSharedData,
+        bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+        clock::Clock,
+        instruction::{AccountMeta, Instruction, InstructionError},
         native_token::*,
         program_pack::Pack,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
-        transaction::Transaction,
+        transaction::{Transaction, TransactionError},
+        transport::TransportError,
     },
     spl_token::{
         self,
         instruction::*,
         state::{Account, Mint},
     },
+    std::sync::{Mutex, Once},
 };
 
-const PROGRAM_NAME: &'static str = "degendevil";
+const PROGRAM_NAME: &str = "degendevil";
 
 pub type Error = Box<dyn std::error::Error>;
 
 pub type CommandResult = Result<(), Error>;
 
+/// Seconds an oracle request may stay unanswered before it can be refunded
+pub const REFUND_TIMEOUT: i64 = 300;
+
+/// Bankroll `setup_house` opens the treasury with, covering the payouts of the test coins
+pub const HOUSE_BANKROLL: u64 = 1000000;
+
+/// Target the runtime logs the output of the programs to
+const PROGRAM_LOG_TARGET: &str = "solana_runtime::message_processor::stable_log";
+
+/// Every program log line of the test binary, the tests of which share the logger
+static PROGRAM_LOGS: Lazy<Mutex<Vec<String>>> = Lazy::new(Mutex::default);
+
+struct ProgramLogCollector;
+
+impl log::Log for ProgramLogCollector {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == PROGRAM_LOG_TARGET
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            PROGRAM_LOGS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the log collector ahead of the logger of the program test, which then leaves it be.
+fn collect_program_logs() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let _ = log::set_logger(&ProgramLogCollector);
+    });
+}
+
+/// Decodes the events of type `T` emitted so far by any test of the binary.
+pub fn events<T: Event>() -> Vec<T> {
+    PROGRAM_LOGS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|line| line.strip_prefix("Program log: "))
+        .filter_map(|data| base64::decode(data).ok())
+        .filter(|data| data.len() >= 8 && data[..8] == T::discriminator())
+        .filter_map(|data| T::try_from_slice(&data[8..]).ok())
+        .collect()
+}
+
 pub async fn create_token(decimals: u8, ctx: &mut ProgramTestContext) -> Result<Keypair, Error> {
     let token = Keypair::new();
 
@@ -77,7 +138,7 @@ pub async fn create_token_account(
                 Account::LEN as u64,
                 &spl_token::id(),
             ),
-            initialize_account(&spl_token::id(), &account.pubkey(), &token, &owner.pubkey())?,
+            initialize_account(&spl_token::id(), &account.pubkey(), token, &owner.pubkey())?,
         ],
         Some(&ctx.payer.pubkey()),
         &[&ctx.payer, &account],
@@ -99,8 +160,8 @@ pub async fn mint_token(
     let transaction = Transaction::new_signed_with_payer(
         &[mint_to(
             &spl_token::id(),
-            &token,
-            &recipient,
+            token,
+            recipient,
             &ctx.payer.pubkey(),
             &[&ctx.payer.pubkey()],
             amount,
@@ -115,6 +176,906 @@ pub async fn mint_token(
     Ok(ctx.banks_client.process_transaction(transaction).await?)
 }
 
+pub async fn transfer_token(
+    from: &Pubkey,
+    to: &Pubkey,
+    owner: &Keypair,
+    amount: u64,
+    ctx: &mut ProgramTestContext,
+) -> CommandResult {
+    let transaction = Transaction::new_signed_with_payer(
+        &[transfer(
+            &spl_token::id(),
+            from,
+            to,
+            &owner.pubkey(),
+            &[&owner.pubkey()],
+            amount,
+        )?],
+        Some(&ctx.payer.pubkey()),
+        &[&ctx.payer, owner],
+        ctx.last_blockhash,
+    );
+
+    Ok(ctx.banks_client.process_transaction(transaction).await?)
+}
+
+pub async fn token_account(
+    account: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> Result<Account, Error> {
+    let account = ctx
+        .banks_client
+        .get_account(*account)
+        .await?
+        .ok_or("Token account not found")?;
+
+    Ok(Account::unpack(&account.data)?)
+}
+
+pub async fn token_balance(account: &Pubkey, ctx: &mut ProgramTestContext) -> Result<u64, Error> {
+    Ok(token_account(account, ctx).await?.amount)
+}
+
+/// Fetches and deserializes an Anchor account.
+pub async fn get_account<T: AccountDeserialize>(
+    address: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> Result<T, Error> {
+    let account = ctx
+        .banks_client
+        .get_account(*address)
+        .await?
+        .ok_or("Account not found")?;
+
+    Ok(T::try_deserialize(&mut account.data.as_slice())?)
+}
+
+pub async fn process_instruction(
+    ix: Instruction,
+    signers: &[&Keypair],
+    ctx: &mut ProgramTestContext,
+) -> CommandResult {
+    let mut all_signers = vec![&ctx.payer];
+    all_signers.extend_from_slice(signers);
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&ctx.payer.pubkey()),
+        &all_signers,
+        ctx.last_blockhash,
+    );
+
+    Ok(ctx.banks_client.process_transaction(transaction).await?)
+}
+
+/// Custom error code the instruction of a failed `result` returned, if any.
+pub fn error_code(result: CommandResult) -> Option<u32> {
+    match result.err()?.downcast_ref::<TransportError>()? {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => Some(*code),
+        _ => None,
+    }
+}
+
+/// Creates the coin counter of `initiator`, its first coin gets nonce 0.
+pub async fn init_counter(
+    initiator: &Keypair,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let (counter, counter_bump) = degendevil::counter_pda(&initiator.pubkey());
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitCounter {
+            counter,
+            initiator: initiator.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitCounter { counter_bump }.data(),
+    };
+
+    process_instruction(ix, &[initiator], ctx).await?;
+
+    Ok(counter)
+}
+
+/// Answers the pending request of the requester owned by `coin` with `random`.
+pub async fn publish_random(
+    oracle: &Keypair,
+    coin: &Pubkey,
+    random: [u8; 64],
+    ctx: &mut ProgramTestContext,
+) -> CommandResult {
+    let mut accounts = degenrand::accounts::PublishRandom {
+        oracle: oracle.pubkey(),
+        vault: degenrand::vault_pda(coin).0,
+        system_program: anchor_lang::solana_program::system_program::id(),
+    }
+    .to_account_metas(None);
+
+    accounts.push(AccountMeta::new(degenrand::requestor_pda(coin).0, false));
+
+    let ix = Instruction {
+        program_id: degenrand::id(),
+        accounts,
+        data: degenrand::instruction::PublishRandom {
+            pkt_id: [0u8; 32],
+            random,
+            tls_id: [0u8; 32],
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[oracle], ctx).await
+}
+
+/// Creates the coin numbered `nonce` of `initiator`, answered by `oracle`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_coin_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    mint: &Pubkey,
+    prize_vault: &Pubkey,
+    oracle: &Pubkey,
+    nonce: u64,
+    amount: u64,
+    decimals: u8,
+    p2p: bool,
+    side: u8,
+    delegated: bool,
+    referrer: Option<Pubkey>,
+) -> Instruction {
+    let (coin_pda, coin_bump) = degendevil::coin_pda(initiator, nonce);
+    let (vault_pda, vault_bump) = degendevil::vault_pda(mint, initiator, nonce);
+
+    let mut accounts = degendevil::accounts::CreateCoin {
+        counter: degendevil::counter_pda(initiator).0,
+        coin: coin_pda,
+        vault: vault_pda,
+        escrow: degendevil::escrow_pda(&vault_pda).0,
+        initiator: *initiator,
+        requester: degenrand::requestor_pda(&coin_pda).0,
+        oracle_vault: degenrand::vault_pda(&coin_pda).0,
+        oracle: *oracle,
+        initiator_ata: *initiator_ata,
+        mint: *mint,
+        config: degendevil::config_pda().0,
+        mint_config: degendevil::mint_config_pda(mint).0,
+        tier_table: degendevil::tier_table_pda(mint).0,
+        treasury: degendevil::treasury_pda(mint).0,
+        treasury_token: degendevil::treasury_token_pda(mint).0,
+        jackpot: degendevil::jackpot_pda(mint).0,
+        prize_vault: *prize_vault,
+        player_stats: degendevil::player_stats_pda(initiator, mint).0,
+        degenrand_program: degenrand::id(),
+        rent: anchor_lang::solana_program::sysvar::rent::id(),
+        token_program: spl_token::id(),
+        system_program: anchor_lang::solana_program::system_program::id(),
+    }
+    .to_account_metas(None);
+
+    // The referrer's token account is checked against the mint of the coin
+    if let Some(referrer) = referrer {
+        accounts.push(AccountMeta::new_readonly(referrer, false));
+    }
+
+    Instruction {
+        program_id: id(),
+        accounts,
+        data: degendevil::instruction::CreateCoin {
+            amount,
+            decimals,
+            coin_bump,
+            vault_bump,
+            p2p,
+            side,
+            delegated,
+            referrer,
+        }
+        .data(),
+    }
+}
+
+/// Creates the SOL coin numbered `nonce` of `initiator`, answered by `oracle`.
+pub fn create_sol_coin_ix(
+    initiator: &Pubkey,
+    oracle: &Pubkey,
+    nonce: u64,
+    amount: u64,
+) -> Instruction {
+    let (coin_pda, coin_bump) = degendevil::coin_pda(initiator, nonce);
+    let (vault_pda, vault_bump) =
+        degendevil::vault_pda(&spl_token::native_mint::id(), initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::CreateSolCoin {
+            counter: degendevil::counter_pda(initiator).0,
+            coin: coin_pda,
+            vault: vault_pda,
+            requester: degenrand::requestor_pda(&coin_pda).0,
+            oracle_vault: degenrand::vault_pda(&coin_pda).0,
+            oracle: *oracle,
+            initiator: *initiator,
+            config: degendevil::config_pda().0,
+            mint_config: degendevil::mint_config_pda(&spl_token::native_mint::id()).0,
+            tier_table: degendevil::tier_table_pda(&spl_token::native_mint::id()).0,
+            treasury: degendevil::treasury_pda(&spl_token::native_mint::id()).0,
+            player_stats: degendevil::player_stats_pda(initiator, &spl_token::native_mint::id()).0,
+            degenrand_program: degenrand::id(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::CreateSolCoin {
+            coin_bump,
+            vault_bump,
+            amount,
+        }
+        .data(),
+    }
+}
+
+/// Settles the SOL coin numbered `nonce` of `initiator` against the SOL treasury.
+pub fn reveal_sol_coin_ix(initiator: &Pubkey, nonce: u64) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+    let native_mint = spl_token::native_mint::id();
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::RevealSolCoin {
+            authority: *initiator,
+            initiator: *initiator,
+            recipient: *initiator,
+            coin,
+            vault: degendevil::vault_pda(&native_mint, initiator, nonce).0,
+            receipt: degendevil::receipt_pda(initiator, nonce).0,
+            player_stats: degendevil::player_stats_pda(initiator, &native_mint).0,
+            treasury: degendevil::treasury_pda(&native_mint).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            config: degendevil::config_pda().0,
+            degenrand_program: degenrand::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::RevealSolCoin {}.data(),
+    }
+}
+
+/// Creates the dice numbered `nonce` of `initiator` rolling under `target`, answered by `oracle`.
+#[allow(clippy::too_many_arguments)]
+pub fn create_dice_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    mint: &Pubkey,
+    oracle: &Pubkey,
+    nonce: u64,
+    amount: u64,
+    decimals: u8,
+    target: u8,
+) -> Instruction {
+    let (coin_pda, coin_bump) = degendevil::coin_pda(initiator, nonce);
+    let (vault_pda, vault_bump) = degendevil::vault_pda(mint, initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::CreateDice {
+            counter: degendevil::counter_pda(initiator).0,
+            coin: coin_pda,
+            vault: vault_pda,
+            escrow: degendevil::escrow_pda(&vault_pda).0,
+            requester: degenrand::requestor_pda(&coin_pda).0,
+            oracle_vault: degenrand::vault_pda(&coin_pda).0,
+            oracle: *oracle,
+            initiator: *initiator,
+            initiator_ata: *initiator_ata,
+            mint: *mint,
+            config: degendevil::config_pda().0,
+            mint_config: degendevil::mint_config_pda(mint).0,
+            treasury: degendevil::treasury_pda(mint).0,
+            treasury_token: degendevil::treasury_token_pda(mint).0,
+            player_stats: degendevil::player_stats_pda(initiator, mint).0,
+            degenrand_program: degenrand::id(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::CreateDice {
+            coin_bump,
+            vault_bump,
+            amount,
+            decimals,
+            target,
+        }
+        .data(),
+    }
+}
+
+/// Settles the dice numbered `nonce` of `initiator` against the treasury of `mint`.
+pub fn reveal_dice_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    mint: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::RevealDice {
+            authority: *initiator,
+            initiator: *initiator,
+            recipient: *initiator,
+            initiator_ata: *initiator_ata,
+            coin,
+            vault: degendevil::vault_pda(mint, initiator, nonce).0,
+            escrow: degendevil::escrow_pda(&degendevil::vault_pda(mint, initiator, nonce).0).0,
+            receipt: degendevil::receipt_pda(initiator, nonce).0,
+            player_stats: degendevil::player_stats_pda(initiator, mint).0,
+            treasury: degendevil::treasury_pda(mint).0,
+            treasury_token: degendevil::treasury_token_pda(mint).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            config: degendevil::config_pda().0,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::RevealDice {}.data(),
+    }
+}
+
+/// Requests the random number for the house coin numbered `nonce` of `initiator`.
+pub fn flip_coin_ix(initiator: &Pubkey, mint: &Pubkey, nonce: u64, oracle: &Pubkey) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::FlipCoin {
+            coin,
+            vault: degendevil::vault_pda(mint, initiator, nonce).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            initiator: *initiator,
+            oracle: *oracle,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            config: degendevil::config_pda().0,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::FlipCoin {}.data(),
+    }
+}
+
+pub async fn flip_coin(
+    initiator: &Keypair,
+    mint: &Pubkey,
+    nonce: u64,
+    oracle: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> CommandResult {
+    let ix = flip_coin_ix(&initiator.pubkey(), mint, nonce, oracle);
+
+    process_instruction(ix, &[initiator], ctx).await
+}
+
+/// Cancels a coin that was never flipped, returning the stake to the initiator.
+pub fn cancel_coin_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    mint: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::CancelCoin {
+            coin,
+            vault: degendevil::vault_pda(mint, initiator, nonce).0,
+            escrow: degendevil::escrow_pda(&degendevil::vault_pda(mint, initiator, nonce).0).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            initiator: *initiator,
+            recipient: *initiator,
+            initiator_ata: *initiator_ata,
+            treasury: degendevil::treasury_pda(mint).0,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::CancelCoin {}.data(),
+    }
+}
+
+/// Settles the house coin numbered `nonce` of `initiator`, paying Token B of `prize_mint`.
+pub fn reveal_coin_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    fee_recipient: &Pubkey,
+    mint: &Pubkey,
+    prize_mint: &Pubkey,
+    initiator_prize_ata: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+
+    let accounts = degendevil::accounts::RevealCoin {
+        authority: *initiator,
+        initiator: *initiator,
+        recipient: *initiator,
+        initiator_ata: *initiator_ata,
+        mint: *mint,
+        coin,
+        vault: degendevil::vault_pda(mint, initiator, nonce).0,
+        escrow: degendevil::escrow_pda(&degendevil::vault_pda(mint, initiator, nonce).0).0,
+        receipt: degendevil::receipt_pda(initiator, nonce).0,
+        player_stats: degendevil::player_stats_pda(initiator, mint).0,
+        prize_vault: degendevil::prize_vault_pda(prize_mint).0,
+        prize_token: degendevil::prize_token_pda(prize_mint).0,
+        initiator_prize_ata: *initiator_prize_ata,
+        treasury: degendevil::treasury_pda(mint).0,
+        treasury_token: degendevil::treasury_token_pda(mint).0,
+        jackpot: degendevil::jackpot_pda(mint).0,
+        jackpot_token: degendevil::jackpot_token_pda(mint).0,
+        config: degendevil::config_pda().0,
+        fee_recipient: *fee_recipient,
+        requester: degenrand::requestor_pda(&coin).0,
+        oracle_vault: degenrand::vault_pda(&coin).0,
+        degenrand_program: degenrand::id(),
+        token_program: spl_token::id(),
+        system_program: anchor_lang::solana_program::system_program::id(),
+    }
+    .to_account_metas(None);
+
+    Instruction {
+        program_id: id(),
+        accounts,
+        data: degendevil::instruction::RevealCoin {}.data(),
+    }
+}
+
+/// Matches the P2P coin numbered `nonce` of `initiator` with the stake of `acceptor`.
+pub fn accept_coin_ix(
+    initiator: &Pubkey,
+    acceptor: &Pubkey,
+    acceptor_ata: &Pubkey,
+    mint: &Pubkey,
+    oracle: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let (coin, _) = degendevil::coin_pda(initiator, nonce);
+    let (vault, _) = degendevil::vault_pda(mint, initiator, nonce);
+
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::AcceptCoin {
+            coin,
+            vault,
+            escrow: degendevil::escrow_pda(&vault).0,
+            requester: degenrand::requestor_pda(&coin).0,
+            initiator: *initiator,
+            acceptor: *acceptor,
+            acceptor_ata: *acceptor_ata,
+            acceptor_stats: degendevil::player_stats_pda(acceptor, mint).0,
+            oracle: *oracle,
+            oracle_vault: degenrand::vault_pda(&coin).0,
+            config: degendevil::config_pda().0,
+            degenrand_program: degenrand::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::AcceptCoin {}.data(),
+    }
+}
+
+/// Settles the P2P coin numbered `nonce` of `initiator` accepted by `acceptor`.
+#[allow(clippy::too_many_arguments)]
+pub fn reveal_p2p_coin_ix(
+    initiator: &Pubkey,
+    initiator_ata: &Pubkey,
+    acceptor: &Pubkey,
+    acceptor_ata: &Pubkey,
+    fee_recipient: &Pubkey,
+    mint: &Pubkey,
+    prize_mint: &Pubkey,
+    initiator_prize_ata: &Pubkey,
+    nonce: u64,
+) -> Instruction {
+    let mut ix = reveal_coin_ix(
+        initiator,
+        initiator_ata,
+        fee_recipient,
+        mint,
+        prize_mint,
+        initiator_prize_ata,
+        nonce,
+    );
+
+    ix.accounts.extend([
+        AccountMeta::new_readonly(*acceptor, false),
+        AccountMeta::new(*acceptor_ata, false),
+        AccountMeta::new(degendevil::player_stats_pda(acceptor, mint).0, false),
+    ]);
+
+    ix
+}
+
+pub async fn initialize_config(
+    admin: &Keypair,
+    oracle: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let (config, config_bump) = degendevil::config_pda();
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitializeConfig {
+            config,
+            admin: admin.pubkey(),
+            program_data: program_data_address(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitializeConfig {
+            config_bump,
+            refund_timeout: REFUND_TIMEOUT,
+            oracle: *oracle,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok(config)
+}
+
+/// Registers `fee_recipient` as the account collecting the fees of its mint.
+pub fn set_fee_recipient_ix(admin: &Pubkey, fee_recipient: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::SetFeeRecipient {
+            config: degendevil::config_pda().0,
+            fee_recipient: *fee_recipient,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetFeeRecipient {}.data(),
+    }
+}
+
+/// Opens a token account of `mint` for `admin` and registers it as the fee recipient.
+pub async fn init_fee_recipient(
+    admin: &Keypair,
+    mint: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let fee_recipient = create_token_account(mint, admin, ctx).await?.pubkey();
+
+    let ix = set_fee_recipient_ix(&admin.pubkey(), &fee_recipient);
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok(fee_recipient)
+}
+
+/// Creates the prize vault of `prize_mint` and returns it with its token account.
+pub async fn init_prize_vault(
+    admin: &Keypair,
+    prize_mint: &Pubkey,
+    reward: u64,
+    ctx: &mut ProgramTestContext,
+) -> Result<(Pubkey, Pubkey), Error> {
+    let (prize_vault, prize_vault_bump) = degendevil::prize_vault_pda(prize_mint);
+    let (prize_token, _) = degendevil::prize_token_pda(prize_mint);
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitPrizeVault {
+            config: degendevil::config_pda().0,
+            prize_vault,
+            prize_token,
+            prize_mint: *prize_mint,
+            admin: admin.pubkey(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitPrizeVault {
+            prize_vault_bump,
+            reward,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok((prize_vault, prize_token))
+}
+
+/// The odds the game shipped with, from 10% at 7 Token A up to 75% at 52.5 Token A.
+pub fn default_tiers() -> Vec<degendevil::Tier> {
+    [(700, 1000), (1750, 2500), (3500, 5000), (5250, 7500)]
+        .iter()
+        .map(|&(min_amount, win_bps)| degendevil::Tier {
+            min_amount,
+            win_bps,
+        })
+        .collect()
+}
+
+/// Creates the tier table of `mint` as the admin of the game.
+pub async fn init_tier_table(
+    admin: &Keypair,
+    mint: &Pubkey,
+    tiers: Vec<degendevil::Tier>,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let (tier_table, tier_table_bump) = degendevil::tier_table_pda(mint);
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitTierTable {
+            config: degendevil::config_pda().0,
+            tier_table,
+            mint: *mint,
+            admin: admin.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitTierTable {
+            tier_table_bump,
+            tiers,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok(tier_table)
+}
+
+/// Allows `mint` to be staked for Token B of `prize_mint`, its tier table must exist.
+pub async fn init_mint_config(
+    admin: &Keypair,
+    mint: &Pubkey,
+    prize_mint: &Pubkey,
+    min_bet: u64,
+    max_bet: u64,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let (mint_config, mint_config_bump) = degendevil::mint_config_pda(mint);
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitMintConfig {
+            config: degendevil::config_pda().0,
+            mint_config,
+            tier_table: degendevil::tier_table_pda(mint).0,
+            mint: *mint,
+            admin: admin.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitMintConfig {
+            mint_config_bump,
+            enabled: true,
+            min_bet,
+            max_bet,
+            prize_mint: *prize_mint,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok(mint_config)
+}
+
+/// Creates the treasury of `mint` and returns it with its token account.
+pub async fn init_treasury(
+    admin: &Keypair,
+    mint: &Pubkey,
+    ctx: &mut ProgramTestContext,
+) -> Result<(Pubkey, Pubkey), Error> {
+    let (treasury, treasury_bump) = degendevil::treasury_pda(mint);
+    let (treasury_token, _) = degendevil::treasury_token_pda(mint);
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitTreasury {
+            config: degendevil::config_pda().0,
+            treasury,
+            treasury_token,
+            mint: *mint,
+            admin: admin.pubkey(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitTreasury { treasury_bump }.data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok((treasury, treasury_token))
+}
+
+/// Creates the jackpot of `mint` and returns it with its token account.
+pub async fn init_jackpot(
+    admin: &Keypair,
+    mint: &Pubkey,
+    contribution_bps: u16,
+    odds_ppm: u32,
+    ctx: &mut ProgramTestContext,
+) -> Result<(Pubkey, Pubkey), Error> {
+    let (jackpot, jackpot_bump) = degendevil::jackpot_pda(mint);
+    let (jackpot_token, _) = degendevil::jackpot_token_pda(mint);
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitJackpot {
+            config: degendevil::config_pda().0,
+            jackpot,
+            jackpot_token,
+            mint: *mint,
+            admin: admin.pubkey(),
+            rent: anchor_lang::solana_program::sysvar::rent::id(),
+            token_program: spl_token::id(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitJackpot {
+            jackpot_bump,
+            contribution_bps,
+            odds_ppm,
+        }
+        .data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok((jackpot, jackpot_token))
+}
+
+/// Creates the SOL treasury and funds it with `bankroll` lamports of `admin`.
+pub async fn init_sol_treasury(
+    admin: &Keypair,
+    bankroll: u64,
+    ctx: &mut ProgramTestContext,
+) -> Result<Pubkey, Error> {
+    let (treasury, treasury_bump) = degendevil::treasury_pda(&spl_token::native_mint::id());
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::InitSolTreasury {
+            config: degendevil::config_pda().0,
+            treasury,
+            admin: admin.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::InitSolTreasury { treasury_bump }.data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    let ix = Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::DepositSolBankroll {
+            config: degendevil::config_pda().0,
+            treasury,
+            admin: admin.pubkey(),
+            system_program: anchor_lang::solana_program::system_program::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::DepositSolBankroll { amount: bankroll }.data(),
+    };
+
+    process_instruction(ix, &[admin], ctx).await?;
+
+    Ok(treasury)
+}
+
+/// Moves `amount` from `source` into the bankroll of `mint` as `admin`.
+pub fn deposit_bankroll_ix(
+    admin: &Pubkey,
+    mint: &Pubkey,
+    source: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: id(),
+        accounts: degendevil::accounts::DepositBankroll {
+            config: degendevil::config_pda().0,
+            treasury: degendevil::treasury_pda(mint).0,
+            treasury_token: degendevil::treasury_token_pda(mint).0,
+            source: *source,
+            admin: *admin,
+            token_program: spl_token::id(),
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::DepositBankroll { amount }.data(),
+    }
+}
+
+/// Moves the on-chain clock `seconds` into the future.
+pub async fn warp_clock(seconds: i64, ctx: &mut ProgramTestContext) -> CommandResult {
+    let mut clock: Clock = ctx.banks_client.get_sysvar().await?;
+    clock.unix_timestamp += seconds;
+    ctx.set_sysvar(&clock);
+
+    // Retried transactions must not share a signature with the ones sent before the warp
+    ctx.last_blockhash = ctx
+        .banks_client
+        .get_new_latest_blockhash(&ctx.last_blockhash)
+        .await?;
+
+    Ok(())
+}
+
+/// Accounts of a house taking coins in a mint, see `setup_house`.
+pub struct House {
+    pub config: Pubkey,
+    pub prize_vault: Pubkey,
+    pub prize_token: Pubkey,
+    pub tier_table: Pubkey,
+    pub treasury: Pubkey,
+    pub treasury_token: Pubkey,
+    pub jackpot: Pubkey,
+    pub jackpot_token: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub mint_config: Pubkey,
+    pub counter: Pubkey,
+}
+
+/// Opens everything a coin in `mint` played for `prize_mint` needs, then the counter of
+/// `initiator`. The coins are answered by `oracle`. The house starts with the default tiers, no reward, a bankroll of
+/// `HOUSE_BANKROLL`, an empty jackpot and no bet limits.
+pub async fn setup_house(
+    admin: &Keypair,
+    oracle: &Pubkey,
+    mint: &Pubkey,
+    prize_mint: &Pubkey,
+    initiator: &Keypair,
+    ctx: &mut ProgramTestContext,
+) -> Result<House, Error> {
+    let config = initialize_config(admin, oracle, ctx).await?;
+    let (prize_vault, prize_token) = init_prize_vault(admin, prize_mint, 0, ctx).await?;
+    let tier_table = init_tier_table(admin, mint, default_tiers(), ctx).await?;
+    let (treasury, treasury_token) = init_treasury(admin, mint, ctx).await?;
+    mint_token(mint, &treasury_token, HOUSE_BANKROLL, ctx).await?;
+    let (jackpot, jackpot_token) = init_jackpot(admin, mint, 0, 0, ctx).await?;
+    let fee_recipient = init_fee_recipient(admin, mint, ctx).await?;
+    let mint_config = init_mint_config(admin, mint, prize_mint, 0, u64::MAX, ctx).await?;
+    let counter = init_counter(initiator, ctx).await?;
+
+    Ok(House {
+        config,
+        prize_vault,
+        prize_token,
+        tier_table,
+        treasury,
+        treasury_token,
+        jackpot,
+        jackpot_token,
+        fee_recipient,
+        mint_config,
+        counter,
+    })
+}
+
 async fn check_fee_payer_balance(
     ctx: &mut ProgramTestContext,
     required_balance: u64,
@@ -145,9 +1106,14 @@ pub struct TestContext {
 pub async fn get_program_test_context() -> Result<TestContext, Error> {
     let program_id = id();
 
+    collect_program_logs();
+
     let mut test_validator =
         ProgramTest::new(PROGRAM_NAME, program_id, processor!(degendevil::entry));
 
+    // The program logs are debug records, whatever RUST_LOG says
+    log::set_max_level(log::max_level().max(log::LevelFilter::Debug));
+
     test_validator.add_program("degenrand", degenrand::id(), None);
 
     let (alice, a_acc) = get_keypair_and_account(10000000000000000);
@@ -160,6 +1126,15 @@ pub async fn get_program_test_context() -> Result<TestContext, Error> {
     test_validator.add_account(oracle.pubkey(), oracle_acc.into());
     test_validator.add_account(winner_mint_holder.pubkey(), winner_mint_holder_acc.into());
 
+    // Present on every cluster, the SOL coins key their accounts by it
+    test_validator.add_account(spl_token::native_mint::id(), native_mint_account().into());
+
+    // The program is deployed with winner_mint_holder as its upgrade authority
+    test_validator.add_account(
+        program_data_address(),
+        program_data_account(&winner_mint_holder.pubkey()).into(),
+    );
+
     let ctx = test_validator.start_with_context().await;
 
     Ok(TestContext {
@@ -171,6 +1146,42 @@ pub async fn get_program_test_context() -> Result<TestContext, Error> {
     })
 }
 
+/// ProgramData account of degendevil under the upgradeable loader.
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[id().as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+fn program_data_account(upgrade_authority: &Pubkey) -> AccountSharedData {
+    AccountSharedData::new_data(
+        LAMPORTS_PER_SOL,
+        &UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        },
+        &bpf_loader_upgradeable::id(),
+    )
+    .unwrap()
+}
+
+fn native_mint_account() -> AccountSharedData {
+    let mut data = vec![0; Mint::LEN];
+
+    Mint::pack(
+        Mint {
+            decimals: spl_token::native_mint::DECIMALS,
+            is_initialized: true,
+            ..Mint::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+
+    let mut account = AccountSharedData::new(LAMPORTS_PER_SOL, Mint::LEN, &spl_token::id());
+    account.set_data(data);
+
+    account
+}
+
 pub fn get_keypair_and_account(amount: u64) -> (Keypair, AccountSharedData) {
     (
         Keypair::new(),
@@ -0,0 +1,331 @@
+#![cfg(feature = "test-bpf")]
+
+mod utils;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use degendevil::DegenErrorCode;
+use {
+    solana_program_test::*,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer},
+    utils::*,
+};
+
+const AMOUNT: u64 = 5250;
+const INITIATOR_FUNDS: u64 = 1000000;
+
+/// Copy of `ix` with the account `original` swapped for `fake`.
+fn spoof(ix: &Instruction, original: &Pubkey, fake: &Pubkey) -> Instruction {
+    let mut ix = ix.clone();
+
+    let meta = ix
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *original)
+        .unwrap();
+    meta.pubkey = *fake;
+
+    ix
+}
+
+#[tokio::test]
+async fn spoofed_accounts_are_rejected() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        oracle,
+        winner_mint_holder,
+        ..
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_other_token_x_account =
+        create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let alice_token_y_account = create_token_account(&token_y.pubkey(), &alice, &mut ctx).await?;
+
+    mint_token(
+        &token_x.pubkey(),
+        &alice_token_x_account.pubkey(),
+        INITIATOR_FUNDS,
+        &mut ctx,
+    )
+    .await?;
+
+    let House {
+        prize_vault,
+        fee_recipient,
+        ..
+    } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let create = |initiator_ata: &Pubkey, nonce: u64| {
+        create_coin_ix(
+            &alice.pubkey(),
+            initiator_ata,
+            &token_x.pubkey(),
+            &prize_vault,
+            &oracle.pubkey(),
+            nonce,
+            AMOUNT,
+            2,
+            false,
+            degendevil::HEADS,
+            false,
+            None,
+        )
+    };
+
+    let (coin, _) = degendevil::coin_pda(&alice.pubkey(), 0);
+    let (other_coin, _) = degendevil::coin_pda(&alice.pubkey(), 1);
+
+    // The requester must be the one of the coin, the oracle the one of the admin and the
+    // stake must be in the coin mint
+    let ix = create(&alice_token_x_account.pubkey(), 0);
+    let spoofed = [
+        (
+            spoof(&ix, &oracle.pubkey(), &Pubkey::new_unique()),
+            DegenErrorCode::InvalidOracle.into(),
+        ),
+        (
+            spoof(
+                &ix,
+                &degenrand::requestor_pda(&coin).0,
+                &degenrand::requestor_pda(&other_coin).0,
+            ),
+            DegenErrorCode::InvalidRequester.into(),
+        ),
+        (
+            spoof(
+                &ix,
+                &degenrand::vault_pda(&coin).0,
+                &degenrand::vault_pda(&other_coin).0,
+            ),
+            DegenErrorCode::InvalidRequester.into(),
+        ),
+        (
+            spoof(&ix, &degenrand::id(), &degendevil::id()),
+            ErrorCode::InvalidProgramId.into(),
+        ),
+        (
+            create(&alice_token_y_account.pubkey(), 0),
+            DegenErrorCode::InvalidMint.into(),
+        ),
+    ];
+
+    for (ix, error) in spoofed {
+        assert_eq!(
+            error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+            Some(error)
+        );
+    }
+
+    for nonce in 0..2 {
+        let ix = create(&alice_token_x_account.pubkey(), nonce);
+        process_instruction(ix, &[&alice], &mut ctx).await?;
+
+        flip_coin(&alice, &token_x.pubkey(), nonce, &oracle.pubkey(), &mut ctx).await?;
+
+        let (coin, _) = degendevil::coin_pda(&alice.pubkey(), nonce);
+        publish_random(&oracle, &coin, [u8::MAX; 64], &mut ctx).await?;
+    }
+
+    let ix = reveal_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &fee_recipient,
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice_token_y_account.pubkey(),
+        0,
+    );
+
+    let spoofed: [(Instruction, u32); 8] = [
+        // Randomness of another coin, or an account degenrand does not own
+        (
+            spoof(
+                &ix,
+                &degenrand::requestor_pda(&coin).0,
+                &degenrand::requestor_pda(&other_coin).0,
+            ),
+            DegenErrorCode::InvalidRequester.into(),
+        ),
+        (
+            spoof(&ix, &degenrand::requestor_pda(&coin).0, &alice.pubkey()),
+            DegenErrorCode::InvalidRequester.into(),
+        ),
+        (
+            spoof(
+                &ix,
+                &degenrand::vault_pda(&coin).0,
+                &degenrand::vault_pda(&other_coin).0,
+            ),
+            DegenErrorCode::InvalidRequester.into(),
+        ),
+        // Any other program in place of degenrand
+        (
+            spoof(&ix, &degenrand::id(), &degendevil::id()),
+            ErrorCode::InvalidProgramId.into(),
+        ),
+        // A coin which is not the one of the vault, caught by its seeds before its requester
+        (
+            spoof(&ix, &coin, &other_coin),
+            ErrorCode::ConstraintSeeds.into(),
+        ),
+        // Accounts outside of the coin mint
+        (
+            spoof(&ix, &token_x.pubkey(), &token_y.pubkey()),
+            DegenErrorCode::InvalidMint.into(),
+        ),
+        (
+            spoof(
+                &ix,
+                &alice_token_x_account.pubkey(),
+                &alice_token_y_account.pubkey(),
+            ),
+            DegenErrorCode::InvalidMint.into(),
+        ),
+        // An ata of the mint other than the one recorded on the coin
+        (
+            spoof(
+                &ix,
+                &alice_token_x_account.pubkey(),
+                &alice_other_token_x_account.pubkey(),
+            ),
+            DegenErrorCode::Unauthorized.into(),
+        ),
+    ];
+
+    for (ix, error) in spoofed {
+        assert_eq!(
+            error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+            Some(error)
+        );
+    }
+
+    // The genuine accounts still settle the coin
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+    assert!(ctx.banks_client.get_account(coin).await?.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn offers_on_another_oracle_are_not_accepted() -> Result<(), Error> {
+    let TestContext {
+        mut ctx,
+        alice,
+        bob,
+        oracle,
+        winner_mint_holder,
+    } = get_program_test_context().await?;
+
+    let token_x = create_token(2, &mut ctx).await?;
+    let token_y = create_token(0, &mut ctx).await?;
+
+    let alice_token_x_account = create_token_account(&token_x.pubkey(), &alice, &mut ctx).await?;
+    let bob_token_x_account = create_token_account(&token_x.pubkey(), &bob, &mut ctx).await?;
+
+    for account in [&alice_token_x_account, &bob_token_x_account] {
+        mint_token(
+            &token_x.pubkey(),
+            &account.pubkey(),
+            INITIATOR_FUNDS,
+            &mut ctx,
+        )
+        .await?;
+    }
+
+    let House { prize_vault, .. } = setup_house(
+        &winner_mint_holder,
+        &oracle.pubkey(),
+        &token_x.pubkey(),
+        &token_y.pubkey(),
+        &alice,
+        &mut ctx,
+    )
+    .await?;
+
+    let ix = create_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        &prize_vault,
+        &oracle.pubkey(),
+        0,
+        AMOUNT,
+        2,
+        true,
+        degendevil::HEADS,
+        false,
+        None,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    // The admin moves to another oracle while the offer is open
+    let new_oracle = Pubkey::new_unique();
+    let set_oracle = |admin: &Pubkey| Instruction {
+        program_id: degendevil::id(),
+        accounts: degendevil::accounts::UpdateConfig {
+            config: degendevil::config_pda().0,
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: degendevil::instruction::SetOracle { oracle: new_oracle }.data(),
+    };
+
+    let ix = set_oracle(&alice.pubkey());
+    assert_eq!(
+        error_code(process_instruction(ix, &[&alice], &mut ctx).await),
+        Some(DegenErrorCode::Unauthorized.into())
+    );
+
+    let ix = set_oracle(&winner_mint_holder.pubkey());
+    process_instruction(ix, &[&winner_mint_holder], &mut ctx).await?;
+
+    // Bob stakes nothing on the oracle the coin was created with
+    for oracle in [oracle.pubkey(), new_oracle] {
+        let ix = accept_coin_ix(
+            &alice.pubkey(),
+            &bob.pubkey(),
+            &bob_token_x_account.pubkey(),
+            &token_x.pubkey(),
+            &oracle,
+            0,
+        );
+        assert_eq!(
+            error_code(process_instruction(ix, &[&bob], &mut ctx).await),
+            Some(DegenErrorCode::InvalidOracle.into())
+        );
+    }
+
+    assert_eq!(
+        token_balance(&bob_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS
+    );
+
+    // Alice still gets her stake back
+    let ix = cancel_coin_ix(
+        &alice.pubkey(),
+        &alice_token_x_account.pubkey(),
+        &token_x.pubkey(),
+        0,
+    );
+    process_instruction(ix, &[&alice], &mut ctx).await?;
+
+    assert_eq!(
+        token_balance(&alice_token_x_account.pubkey(), &mut ctx).await?,
+        INITIATOR_FUNDS
+    );
+
+    Ok(())
+}
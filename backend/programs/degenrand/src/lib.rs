@@ -9,8 +9,6 @@ const VAULT_PREFIX: &str = "DEGENRAND_VAULT_SEED_V1.0";
 const REQUESTOR_PREFIX: &str = "DEGENRAND_REQUESTOR_SEED_V1.0";
 #[program]
 pub mod degenrand {
-    use std::ops::DerefMut;
-
     use crate::ErrorCode;
 
     use super::*;
@@ -62,22 +60,8 @@ pub mod degenrand {
             }
         }
 
-        // Transfer fee to Oracle
-        {
-            let vault = ctx.accounts.vault.to_account_info();
-
-            **vault.try_borrow_mut_lamports()? = vault
-                .lamports()
-                .checked_sub(ORACLE_FEE)
-                .ok_or(ProgramError::InvalidArgument)?;
-
-            **ctx.accounts.oracle.try_borrow_mut_lamports()? = ctx
-                .accounts
-                .oracle
-                .lamports()
-                .checked_add(ORACLE_FEE)
-                .ok_or(ProgramError::InvalidArgument)?;
-        }
+        // The fee stays escrowed in the vault until the Oracle publishes a response,
+        // so it can be refunded if the request expires.
 
         // Once the requester has active_request set, it's frozen until the Oracle responds
         {
@@ -87,6 +71,13 @@ pub mod degenrand {
             requester.last_updated = clock.unix_timestamp;
             requester.active_request = true;
             requester.count += 1;
+
+            emit!(RandomnessRequested {
+                requester: ctx.accounts.requester.key(),
+                authority: requester.authority,
+                oracle: requester.oracle,
+                count: requester.count,
+            });
         }
 
         Ok(())
@@ -107,6 +98,10 @@ pub mod degenrand {
             return Err(ErrorCode::Unauthorized.into());
         }
 
+        if loader.key() != ctx.accounts.vault.requester {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+
         if !requester.active_request {
             return Err(ErrorCode::AlreadyCompleted.into());
         }
@@ -118,6 +113,88 @@ pub mod degenrand {
         requester.pkt_id = pkt_id;
         requester.tls_id = tls_id;
 
+        emit!(RandomnessPublished {
+            requester: loader.key(),
+            oracle: requester.oracle,
+            count: requester.count,
+            random,
+        });
+
+        // Transfer fee to Oracle
+        {
+            let vault = ctx.accounts.vault.to_account_info();
+
+            **vault.try_borrow_mut_lamports()? = vault
+                .lamports()
+                .checked_sub(ORACLE_FEE)
+                .ok_or(ProgramError::InvalidArgument)?;
+
+            **ctx.accounts.oracle.try_borrow_mut_lamports()? = ctx
+                .accounts
+                .oracle
+                .lamports()
+                .checked_add(ORACLE_FEE)
+                .ok_or(ProgramError::InvalidArgument)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Used by the authority to unlock a request the Oracle never answered.
+     * The escrowed fee is refunded to the recipient.
+     */
+    pub fn expire_request(ctx: Context<ExpireRequest>, timeout: i64) -> Result<()> {
+        {
+            let requester_key = ctx.accounts.requester.to_account_info().key();
+
+            if requester_key != ctx.accounts.vault.requester {
+                return Err(ErrorCode::Unauthorized.into());
+            }
+
+            let requester = &mut ctx.accounts.requester.load_mut()?;
+
+            if requester.authority != ctx.accounts.authority.key() {
+                return Err(ErrorCode::Unauthorized.into());
+            }
+
+            if !requester.active_request {
+                return Err(ErrorCode::AlreadyCompleted.into());
+            }
+
+            let clock: Clock = Clock::get().unwrap();
+
+            if clock.unix_timestamp.saturating_sub(requester.last_updated) < timeout {
+                return Err(ErrorCode::RequestNotExpired.into());
+            }
+
+            requester.last_updated = clock.unix_timestamp;
+            requester.active_request = false;
+
+            emit!(RequestExpired {
+                requester: requester_key,
+                recipient: ctx.accounts.recipient.key(),
+                count: requester.count,
+            });
+        }
+
+        // Refund the escrowed fee
+        {
+            let vault = ctx.accounts.vault.to_account_info();
+
+            **vault.try_borrow_mut_lamports()? = vault
+                .lamports()
+                .checked_sub(ORACLE_FEE)
+                .ok_or(ProgramError::InvalidArgument)?;
+
+            **ctx.accounts.recipient.try_borrow_mut_lamports()? = ctx
+                .accounts
+                .recipient
+                .lamports()
+                .checked_add(ORACLE_FEE)
+                .ok_or(ProgramError::InvalidArgument)?;
+        }
+
         Ok(())
     }
 
@@ -135,6 +212,12 @@ pub mod degenrand {
             return Err(ErrorCode::RequesterLocked.into());
         }
 
+        emit!(AuthorityTransferred {
+            requester: ctx.accounts.requester.key(),
+            old_authority: requester.authority,
+            new_authority: ctx.accounts.new_authority.key(),
+        });
+
         requester.authority = ctx.accounts.new_authority.key();
 
         Ok(())
@@ -143,38 +226,30 @@ pub mod degenrand {
     pub fn remove_pdas(ctx: Context<Cleanup>) -> Result<()> {
         let requester = ctx.accounts.requester.load()?;
 
-        if requester.authority != ctx.accounts.initiator.key() {
+        if requester.authority != ctx.accounts.initiator.key()
+            || requester.authority != ctx.accounts.authority.key()
+        {
             return Err(ErrorCode::Unauthorized.into());
         }
 
+        // The escrowed fee must not be swept while the Oracle may still answer
+        if requester.active_request {
+            return Err(ErrorCode::RequesterLocked.into());
+        }
+
         let (vault_pda, _) = vault_pda(&ctx.accounts.initiator.key());
 
         if vault_pda != ctx.accounts.vault.key() {
             return Err(ErrorCode::Unauthorized.into());
         }
 
-        **ctx
-            .accounts
-            .initiator
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() += ctx.accounts.vault.to_account_info().lamports()
-            + ctx.accounts.requester.to_account_info().lamports();
-
-        **ctx
-            .accounts
-            .vault
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() = 0;
-
-        **ctx
-            .accounts
-            .requester
-            .to_account_info()
-            .try_borrow_mut_lamports()?
-            .deref_mut() = 0;
+        emit!(PdasRemoved {
+            requester: ctx.accounts.requester.key(),
+            vault: vault_pda,
+            recipient: ctx.accounts.initiator.key(),
+        });
 
+        // Both accounts are closed to the initiator on exit
         Ok(())
     }
 }
@@ -185,7 +260,7 @@ pub struct Initialize<'info> {
         init,
         seeds = [REQUESTOR_PREFIX.as_bytes(), authority.key().as_ref()],
         bump,
-        payer = authority,
+        payer = payer,
         space = 8 + size_of::<Requester>()
     )]
     pub requester: AccountLoader<'info, Requester>,
@@ -194,14 +269,18 @@ pub struct Initialize<'info> {
         init,
         seeds = [VAULT_PREFIX.as_bytes(), authority.key().as_ref(), crate::id().as_ref()],
         bump,
-        payer = authority,
+        payer = payer,
         space = 8 + size_of::<Vault>()
     )]
     pub vault: Account<'info, Vault>,
 
-    #[account(mut)]
+    /// Owner of the requester, may be a PDA signing through a CPI
     pub authority: Signer<'info>,
 
+    /// Pays the rent of the requester and vault
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
     /// CHECK: The client decides the oracle to use
     #[account(mut)]
     pub oracle: AccountInfo<'info>,
@@ -255,23 +334,45 @@ pub struct PublishRandom<'info> {
     #[account(mut, signer)]
     pub oracle: AccountInfo<'info>,
 
+    /// Vault escrowing the fee of the request
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct Cleanup<'info> {
+pub struct ExpireRequest<'info> {
+    #[account(mut)]
+    pub requester: AccountLoader<'info, Requester>,
+
     #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
     pub authority: Signer<'info>,
 
-    /// CHECK: Return Remaining amount to initiator
+    /// CHECK: The client decides who receives the refunded fee
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Cleanup<'info> {
     #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Closed, its rent returned to the initiator
+    #[account(mut, close = initiator)]
     pub requester: AccountLoader<'info, Requester>,
 
     /// CHECK: initiator to return amount to
     #[account(mut)]
     pub initiator: AccountInfo<'info>,
-    /// CHECK: Return Remaining amount to initiator
-    #[account(mut)]
+
+    /// Closed, the rent and any fee left returned to the initiator
+    #[account(mut, close = initiator)]
     pub vault: Account<'info, Vault>,
 
     pub system_program: Program<'info, System>,
@@ -292,6 +393,46 @@ pub struct TransferAuthority<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event]
+pub struct RandomnessRequested {
+    pub requester: Pubkey,
+    pub authority: Pubkey,
+    pub oracle: Pubkey,
+    /// Number of the request among the requests of the requester
+    pub count: u64,
+}
+
+#[event]
+pub struct RandomnessPublished {
+    pub requester: Pubkey,
+    pub oracle: Pubkey,
+    pub count: u64,
+    pub random: [u8; 64],
+}
+
+#[event]
+pub struct RequestExpired {
+    pub requester: Pubkey,
+    /// Account refunded the escrowed fee
+    pub recipient: Pubkey,
+    pub count: u64,
+}
+
+#[event]
+pub struct PdasRemoved {
+    pub requester: Pubkey,
+    pub vault: Pubkey,
+    /// Account receiving the rent of both
+    pub recipient: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub requester: Pubkey,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("You are not authorized to complete this transaction")]
@@ -308,4 +449,7 @@ pub enum ErrorCode {
 
     #[msg("You cannot change authority of a request awaiting a response")]
     RequesterLocked,
+
+    #[msg("The request has not been awaiting a response for long enough")]
+    RequestNotExpired,
 }
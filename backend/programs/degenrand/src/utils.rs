@@ -7,7 +7,7 @@ pub fn vault_pda(authority: &Pubkey) -> (Pubkey, u8) {
         &[
             VAULT_PREFIX.as_bytes(),
             authority.as_ref(),
-            &crate::id().as_ref(),
+            crate::id().as_ref(),
         ],
         &crate::id(),
     )
@@ -83,6 +83,7 @@ class UserSession extends Session {
                     requester: this.reqAccount,
                     vault: this.vaultAccount,
                     authority: this.keypair.publicKey,
+                    payer: this.keypair.publicKey,
                     oracle: this.oraclePubkey,
                     rent: anchor.web3.SYSVAR_RENT_PUBKEY,
                     systemProgram: anchor.web3.SystemProgram.programId,
@@ -120,6 +121,17 @@ class MockOracleSession extends Session {
         let tlsId = randomBytes(32);
 
         anchor.setProvider(this.provider);
+
+        // The vault escrowing the fee records the requester it pays for
+        const [vault] = await this.program.account.vault.all([
+            {
+                memcmp: {
+                    offset: 8,
+                    bytes: requester.publicKey.toBase58(),
+                },
+            },
+        ]);
+
         await this.program.rpc.publishRandom(
             randomNumber,
             pktId,
@@ -127,7 +139,7 @@ class MockOracleSession extends Session {
             {
                 accounts: {
                     oracle: this.keypair.publicKey,
-                    requester: requester.publicKey,
+                    vault: vault.publicKey,
                     systemProgram: anchor.web3.SystemProgram.programId,
                 },
                 remainingAccounts: [
@@ -36,7 +36,6 @@ export const getTokenBalance = async (
 
 
 export const coinPrefix = "DEGENDEVIL_COIN_SEED_V1.0";
-export const winnerPrefix = "DEGENDEVIL_WINNER_SEED_V1.0";
 export const vaultPrefix = "DEGENDEVIL_VAULT_SEED_V1.0";
 export const randVaultPrefix = "DEGENRAND_VAULT_SEED_V1.0";
 export const requesterPrefix = "DEGENRAND_REQUESTOR_SEED_V1.0";
@@ -63,31 +62,24 @@ export const oracleVaultPda = async (publicKey, programId) => {
 
 };
 
-export const coinPda = async (publicKey, programId) => {
+const nonceBuffer = (nonce) => new anchor.BN(nonce).toArrayLike(Buffer, "le", 8);
+
+export const coinPda = async (publicKey, nonce, programId) => {
     publicKey = new anchor.web3.PublicKey(publicKey);
     programId = new anchor.web3.PublicKey(programId);
     let [coinPdaAddress, coinBump] = await anchor.web3.PublicKey.findProgramAddress(
-        [Buffer.from(coinPrefix), publicKey.toBuffer()],
+        [Buffer.from(coinPrefix), publicKey.toBuffer(), nonceBuffer(nonce)],
         programId
     );
     return { coinPdaAddress, coinBump };
 };
 
-export const winnerPda = async (publicKey, programId) => {
-    publicKey = new anchor.web3.PublicKey(publicKey);
-    programId = new anchor.web3.PublicKey(programId);
-    let [winnerPdaAddress, winnerBump] = await anchor.web3.PublicKey.findProgramAddress(
-        [Buffer.from(winnerPrefix), publicKey.toBuffer()],
-        programId
-    );
-    return { winnerPdaAddress, winnerBump };
-};
-export const vaultPda = async (mintX, publicKey, programId) => {
+export const vaultPda = async (mintX, publicKey, nonce, programId) => {
     mintX = new anchor.web3.PublicKey(mintX);
     publicKey = new anchor.web3.PublicKey(publicKey);
     programId = new anchor.web3.PublicKey(programId);
     let [vaultPdaAddress, vaultBump] = await anchor.web3.PublicKey.findProgramAddress(
-        [Buffer.from(vaultPrefix), mintX.toBuffer(), publicKey.toBuffer(), programId.toBuffer()],
+        [Buffer.from(vaultPrefix), mintX.toBuffer(), publicKey.toBuffer(), nonceBuffer(nonce), programId.toBuffer()],
         programId
     );
     return { vaultPdaAddress, vaultBump };